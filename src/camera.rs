@@ -0,0 +1,503 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::input::touch::Touches;
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use serde::{Deserialize, Serialize};
+use crate::cursor::MainCamera;
+use crate::diagnostics::{escaping, CenterOfMass};
+use crate::input::{action_just_pressed, action_pressed, Action, KeyBindings};
+use crate::selection::Selected;
+use crate::simulation::{Body, BodyConfig, Config, Mass, Position, RenderOrigin, TestParticle, Velocity};
+
+/// Zoom factor per scroll-wheel line.
+const ZOOM_STEP: f32 = 0.9;
+
+/// Orthographic scales the zoom is clamped to.
+const MIN_SCALE: f32 = 1e-4;
+const MAX_SCALE: f32 = 1e4;
+
+/// Number keys for bookmarks 1 to 9: pressed with
+/// `Action::StoreCameraBookmark` held they store the view, alone they
+/// jump back to it.
+const BOOKMARK_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// A saved view, kept in `RenderConfig::camera_bookmarks` so it is written
+/// to the scenario file with the rest of the config.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    /// World position the view was centered on, so the bookmark survives
+    /// the render origin moving.
+    pub center: DVec2,
+    /// Orthographic scale.
+    pub scale: f32,
+}
+
+/// Where the main camera starts, see `RenderConfig::initial_view`. Lets a
+/// scenario open already framed on its bodies.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InitialView {
+    /// Camera position, render space.
+    pub position: Vec3,
+    /// Point the camera faces, with +y up on screen.
+    pub look_at: Vec3,
+    pub projection: ViewProjection,
+}
+
+impl InitialView {
+    pub fn transform(&self) -> Transform {
+        Transform::from_translation(self.position).looking_at(self.look_at, Vec3::Y)
+    }
+}
+
+impl Default for InitialView {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(0., 0., 5.),
+            look_at: Vec3::ZERO,
+            projection: default(),
+        }
+    }
+}
+
+/// Projection of `InitialView`. Panning, zooming and the camera targets
+/// other than `CameraTarget::Free` only drive an orthographic camera.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ViewProjection {
+    Orthographic {
+        scale: f32,
+    },
+    Perspective {
+        /// Vertical field of view, in degrees.
+        fov: f32,
+    },
+}
+
+impl ViewProjection {
+    pub fn projection(self) -> Projection {
+        match self {
+            ViewProjection::Orthographic { scale } => OrthographicProjection {
+                scale: scale.clamp(MIN_SCALE, MAX_SCALE),
+                ..default()
+            }.into(),
+            ViewProjection::Perspective { fov } => PerspectiveProjection {
+                fov: fov.to_radians(),
+                ..default()
+            }.into(),
+        }
+    }
+}
+
+impl Default for ViewProjection {
+    fn default() -> Self {
+        ViewProjection::Orthographic { scale: 0.08 }
+    }
+}
+
+/// What the camera keeps centered.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum CameraTarget {
+    /// Stays where it is; pan with `Action::PanCamera`.
+    #[default]
+    Free,
+    CenterOfMass,
+    /// The `Selected` body. Stays put while nothing is selected.
+    Selected,
+    /// Centers and zooms to keep every body in view, smoothed by
+    /// `RenderConfig::auto_fit_smoothing`. Bodies escaping the rest are
+    /// left out, unless every body is.
+    AutoFit,
+}
+
+impl CameraTarget {
+    fn next(self) -> Self {
+        match self {
+            CameraTarget::Free => CameraTarget::CenterOfMass,
+            CameraTarget::CenterOfMass => CameraTarget::Selected,
+            CameraTarget::Selected => CameraTarget::AutoFit,
+            CameraTarget::AutoFit => CameraTarget::Free,
+        }
+    }
+}
+
+/// An ease from where the camera was when the target changed to the new
+/// target, which may itself be moving.
+#[derive(Resource, Default)]
+struct CameraTween(Option<Tween>);
+
+/// An ease from the view at the press to a recalled bookmark, over
+/// `RenderConfig::camera_transition`. Panning or zooming cancels it.
+#[derive(Resource, Default)]
+struct BookmarkFlight(Option<Flight>);
+
+struct Flight {
+    from: CameraBookmark,
+    to: CameraBookmark,
+    elapsed: f32,
+}
+
+/// Cursor position, in viewport pixels, the last pan step moved from.
+#[derive(Resource, Default)]
+struct PanAnchor(Option<Vec2>);
+
+struct Tween {
+    /// World position, so moving the render origin doesn't disturb the ease.
+    from: DVec2,
+    elapsed: f32,
+}
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<CameraTarget>()
+            .init_resource::<CameraTween>()
+            .init_resource::<PanAnchor>()
+            .init_resource::<BookmarkFlight>()
+            .init_resource::<RenderOrigin>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<Touches>()
+            .add_systems(OnExit(CameraTarget::Free), start_tween)
+            .add_systems(OnExit(CameraTarget::CenterOfMass), start_tween)
+            .add_systems(OnExit(CameraTarget::Selected), start_tween)
+            .add_systems(OnExit(CameraTarget::AutoFit), start_tween)
+            .add_systems(Update, (
+                cycle_target.run_if(action_just_pressed(Action::CycleCameraTarget)),
+                (
+                    start_pan.run_if(action_just_pressed(Action::PanCamera)),
+                    pan.run_if(action_pressed(Action::PanCamera)),
+                    zoom,
+                    pinch_zoom,
+                    store_bookmark.run_if(action_pressed(Action::StoreCameraBookmark)),
+                    recall_bookmark.run_if(not(action_pressed(Action::StoreCameraBookmark))),
+                    fly_to_bookmark,
+                    follow_target,
+                    auto_fit.run_if(in_state(CameraTarget::AutoFit)),
+                ).chain(),
+            ));
+    }
+}
+
+fn cycle_target(state: Res<State<CameraTarget>>, mut next_state: ResMut<NextState<CameraTarget>>) {
+    next_state.set(state.get().next());
+}
+
+fn start_tween(
+    mut tween: ResMut<CameraTween>,
+    origin: Res<RenderOrigin>,
+    camera: Query<&Transform, With<MainCamera>>,
+) {
+    if let Ok(transform) = camera.get_single() {
+        let from = origin.to_world(transform.translation).truncate();
+        tween.0 = Some(Tween { from, elapsed: 0. });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn follow_target(
+    time: Res<Time<Real>>,
+    config: Res<Config>,
+    target: Res<State<CameraTarget>>,
+    center_of_mass: Res<CenterOfMass>,
+    origin: Res<RenderOrigin>,
+    selected: Query<&Position, (With<Body>, With<Selected>)>,
+    mut tween: ResMut<CameraTween>,
+    mut camera: Query<&mut Transform, With<MainCamera>>,
+) {
+    let Ok(mut transform) = camera.get_single_mut() else {
+        return;
+    };
+    let goal = match target.get() {
+        // `auto_fit` moves the camera itself.
+        CameraTarget::Free | CameraTarget::AutoFit => None,
+        CameraTarget::CenterOfMass => center_of_mass.0,
+        CameraTarget::Selected => selected.get_single().ok().map(|position| position.0),
+    };
+    let Some(goal) = goal.map(|goal| goal.truncate()) else {
+        tween.0 = None;
+        return;
+    };
+    let position = match &mut tween.0 {
+        Some(active) => {
+            active.elapsed += time.delta_seconds();
+            let duration = config.render.camera_transition;
+            let t = if duration > 0. { (active.elapsed / duration).min(1.) } else { 1. };
+            let position = active.from.lerp(goal, smoothstep(t) as f64);
+            if t >= 1. {
+                tween.0 = None;
+            }
+            position
+        }
+        None => goal,
+    };
+    let position = (position - origin.0.truncate()).as_vec2();
+    transform.translation = position.extend(transform.translation.z);
+}
+
+#[allow(clippy::type_complexity)]
+fn auto_fit(
+    time: Res<Time<Real>>,
+    config: Res<Config>,
+    origin: Res<RenderOrigin>,
+    bodies: Query<(&Position, &Velocity, &Mass, &BodyConfig, Has<TestParticle>), With<Body>>,
+    mut camera: Query<(&Camera, &mut Transform, &mut Projection), With<MainCamera>>,
+) {
+    let Ok((camera, mut transform, mut projection)) = camera.get_single_mut() else {
+        return;
+    };
+    let Projection::Orthographic(projection) = &mut *projection else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size().filter(|size| size.min_element() > 0.) else {
+        return;
+    };
+    let bodies: Vec<_> = bodies.iter().collect();
+    let states: Vec<_> = bodies.iter()
+        .map(|(position, velocity, mass, _, test_particle)| {
+            (if *test_particle { 0. } else { mass.0 }, position.0, velocity.0)
+        })
+        .collect();
+    // A body flung out of the system would otherwise zoom the view out
+    // after it forever; it's only kept when everything is escaping.
+    let mut escaping = escaping(&states, config.gravitational_constant());
+    if escaping.iter().all(|escaping| *escaping) {
+        escaping.fill(false);
+    }
+    let Some((min, max)) = bodies.iter()
+        .zip(escaping)
+        .filter(|(_, escaping)| !escaping)
+        .map(|((position, _, _, body, _), _)| {
+            let center = origin.to_render(position.0).truncate();
+            let radius = Vec2::splat(body.radius as f32);
+            (center - radius, center + radius)
+        })
+        .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    else {
+        return;
+    };
+    let render = &config.render;
+    let center = (min + max) / 2.;
+    // With the default `ScalingMode::WindowSize`, one pixel spans `scale` world units.
+    let fitted_scale = ((max - min) * render.auto_fit_margin / viewport).max_element()
+        .clamp(MIN_SCALE, MAX_SCALE);
+
+    let dt = time.delta_seconds();
+    let tau = render.auto_fit_smoothing.max(0.);
+    let blend = |tau: f32| if tau > 0. { 1. - (-dt / tau).exp() } else { 1. };
+    let zoom_tau = if fitted_scale < projection.scale { tau * 4. } else { tau };
+    projection.scale += (fitted_scale - projection.scale) * blend(zoom_tau);
+    let position = transform.translation.truncate().lerp(center, blend(tau));
+    transform.translation = position.extend(transform.translation.z);
+}
+
+/// Where the camera is centered and how far it is zoomed, if it is an
+/// orthographic main camera.
+fn current_view(origin: &RenderOrigin, transform: &Transform, projection: &Projection) -> Option<CameraBookmark> {
+    let Projection::Orthographic(projection) = projection else {
+        return None;
+    };
+    Some(CameraBookmark {
+        center: origin.to_world(transform.translation).truncate(),
+        scale: projection.scale,
+    })
+}
+
+fn store_bookmark(
+    keys: Res<ButtonInput<KeyCode>>,
+    origin: Res<RenderOrigin>,
+    mut config: ResMut<Config>,
+    camera: Query<(&Transform, &Projection), With<MainCamera>>,
+) {
+    let Ok((transform, projection)) = camera.get_single() else {
+        return;
+    };
+    for (slot, key) in (1..).zip(BOOKMARK_KEYS) {
+        if !keys.just_pressed(key) {
+            continue;
+        }
+        if let Some(view) = current_view(&origin, transform, projection) {
+            config.render.camera_bookmarks.insert(slot, view);
+            info!("stored camera bookmark {slot}");
+        }
+    }
+}
+
+fn recall_bookmark(
+    keys: Res<ButtonInput<KeyCode>>,
+    origin: Res<RenderOrigin>,
+    config: Res<Config>,
+    camera: Query<(&Transform, &Projection), With<MainCamera>>,
+    mut flight: ResMut<BookmarkFlight>,
+    mut next_target: ResMut<NextState<CameraTarget>>,
+) {
+    let Ok((transform, projection)) = camera.get_single() else {
+        return;
+    };
+    let recalled = (1..).zip(BOOKMARK_KEYS)
+        .filter(|(_, key)| keys.just_pressed(*key))
+        .find_map(|(slot, _)| config.render.camera_bookmarks.get(&slot));
+    let (Some(to), Some(from)) = (recalled, current_view(&origin, transform, projection)) else {
+        return;
+    };
+    // A follow target would drag the view straight off the bookmark.
+    next_target.set(CameraTarget::Free);
+    flight.0 = Some(Flight { from, to: *to, elapsed: 0. });
+}
+
+fn fly_to_bookmark(
+    time: Res<Time<Real>>,
+    config: Res<Config>,
+    origin: Res<RenderOrigin>,
+    target: Res<State<CameraTarget>>,
+    mut flight: ResMut<BookmarkFlight>,
+    mut camera: Query<(&mut Transform, &mut Projection), With<MainCamera>>,
+) {
+    // Picking a follow target mid-flight takes over.
+    if target.is_changed() && *target.get() != CameraTarget::Free {
+        flight.0 = None;
+    }
+    let Some(active) = &mut flight.0 else {
+        return;
+    };
+    let Ok((mut transform, mut projection)) = camera.get_single_mut() else {
+        return;
+    };
+    let Projection::Orthographic(projection) = &mut *projection else {
+        return;
+    };
+    active.elapsed += time.delta_seconds();
+    let duration = config.render.camera_transition;
+    let t = if duration > 0. { (active.elapsed / duration).min(1.) } else { 1. };
+    let (center, scale) = if t >= 1. {
+        (active.to.center, active.to.scale)
+    } else {
+        let eased = smoothstep(t);
+        (
+            active.from.center.lerp(active.to.center, eased as f64),
+            // Geometric, so zooming feels even at every scale.
+            active.from.scale * (active.to.scale / active.from.scale).powf(eased),
+        )
+    };
+    let position = (center - origin.0.truncate()).as_vec2();
+    transform.translation = position.extend(transform.translation.z);
+    projection.scale = scale;
+    if t >= 1. {
+        flight.0 = None;
+    }
+}
+
+/// Eases in and out, with zero slope at both ends.
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3. - 2. * t)
+}
+
+fn start_pan(mut anchor: ResMut<PanAnchor>, window: Query<&Window, With<PrimaryWindow>>) {
+    anchor.0 = window.get_single().ok().and_then(|window| window.cursor_position());
+}
+
+/// Drags the view with the cursor. Panning takes over from any follow
+/// target, cancelling a transition in progress.
+fn pan(
+    mut anchor: ResMut<PanAnchor>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut camera: Query<(&mut Transform, &Projection), With<MainCamera>>,
+    mut tween: ResMut<CameraTween>,
+    mut flight: ResMut<BookmarkFlight>,
+    target: Res<State<CameraTarget>>,
+    mut next_target: ResMut<NextState<CameraTarget>>,
+) {
+    let cursor = window.get_single().ok().and_then(|window| window.cursor_position());
+    let (Some(previous), Some(cursor)) = (anchor.0, cursor) else {
+        return;
+    };
+    anchor.0 = Some(cursor);
+    let Ok((mut transform, Projection::Orthographic(projection))) = camera.get_single_mut() else {
+        return;
+    };
+    let delta = cursor - previous;
+    if delta == Vec2::ZERO {
+        return;
+    }
+    // Viewport y points down; one logical pixel spans `scale` world units.
+    transform.translation += Vec3::new(-delta.x, delta.y, 0.) * projection.scale;
+    tween.0 = None;
+    flight.0 = None;
+    if *target.get() != CameraTarget::Free {
+        next_target.set(CameraTarget::Free);
+    }
+}
+
+/// Scroll to zoom. Only changes the scale, so it never fights a transition,
+/// but it does take over from `CameraTarget::AutoFit`.
+fn zoom(
+    mut wheel: EventReader<MouseWheel>,
+    mut camera: Query<&mut Projection, With<MainCamera>>,
+    mut flight: ResMut<BookmarkFlight>,
+    target: Res<State<CameraTarget>>,
+    mut next_target: ResMut<NextState<CameraTarget>>,
+) {
+    let lines: f32 = wheel.read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 40.,
+        })
+        .sum();
+    if lines == 0. {
+        return;
+    }
+    flight.0 = None;
+    if *target.get() == CameraTarget::AutoFit {
+        next_target.set(CameraTarget::Free);
+    }
+    if let Ok(mut projection) = camera.get_single_mut() {
+        if let Projection::Orthographic(projection) = &mut *projection {
+            projection.scale = (projection.scale * ZOOM_STEP.powf(lines)).clamp(MIN_SCALE, MAX_SCALE);
+        }
+    }
+}
+
+/// `zoom` for touch screens: two fingers spreading apart zoom in by as
+/// much as they spread, pinching together zooms out.
+fn pinch_zoom(
+    touches: Res<Touches>,
+    mut spread: Local<Option<f32>>,
+    mut camera: Query<&mut Projection, With<MainCamera>>,
+    mut flight: ResMut<BookmarkFlight>,
+    target: Res<State<CameraTarget>>,
+    mut next_target: ResMut<NextState<CameraTarget>>,
+) {
+    let mut fingers = touches.iter();
+    let (Some(a), Some(b), None) = (fingers.next(), fingers.next(), fingers.next()) else {
+        *spread = None;
+        return;
+    };
+    let distance = a.position().distance(b.position());
+    // Measured against the last frame rather than `Touch::previous_position`,
+    // which only moves on frames the finger does.
+    let Some(previous) = spread.replace(distance) else {
+        return;
+    };
+    if distance <= 0. || previous <= 0. || distance == previous {
+        return;
+    }
+    flight.0 = None;
+    if *target.get() == CameraTarget::AutoFit {
+        next_target.set(CameraTarget::Free);
+    }
+    if let Ok(mut projection) = camera.get_single_mut() {
+        if let Projection::Orthographic(projection) = &mut *projection {
+            projection.scale = (projection.scale * previous / distance).clamp(MIN_SCALE, MAX_SCALE);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::camera::CameraTarget;
+use crate::cursor::MainCamera;
+use crate::diagnostics::MinDistance;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::{Config, Position, RenderOrigin, TimeDilation};
+
+/// Whether close approaches slow the simulation down for effect.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum CinematicState {
+    #[default]
+    Off,
+    On,
+}
+
+/// Slow motion on close approaches, see `RenderConfig::cinematic`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CinematicConfig {
+    /// Center-to-center distance of the closest pair, in scenario units,
+    /// below which time slows down. It slows in proportion to the distance
+    /// from there.
+    pub threshold: f64,
+    /// Slowest `TimeDilation`, reached when the pair is this fraction of
+    /// `threshold` apart or closer.
+    pub min_time_scale: f64,
+    /// Time constant, in real seconds, of easing into and out of slow
+    /// motion.
+    pub smoothing: f32,
+    /// Zoom in by up to this factor, about the closest pair, at the slowest;
+    /// 1 leaves the camera alone. Skipped under `CameraTarget::AutoFit`,
+    /// which sets the zoom itself.
+    pub zoom: f32,
+}
+
+impl Default for CinematicConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 5.,
+            min_time_scale: 0.1,
+            smoothing: 0.3,
+            zoom: 2.,
+        }
+    }
+}
+
+/// Zoom factor the cinematic mode has applied on top of the user's zoom.
+#[derive(Resource)]
+struct CinematicZoom(f32);
+
+impl Default for CinematicZoom {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// Eases `TimeDilation` down while the `MinDistance` pair is within
+/// `CinematicConfig::threshold`, and back up once it has passed. Purely for
+/// presentation: it changes how fast simulated time passes, never how
+/// accurately a step is integrated.
+pub struct CinematicPlugin;
+
+impl Plugin for CinematicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<CinematicState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<TimeDilation>()
+            .init_resource::<CinematicZoom>()
+            .add_systems(Update, (
+                (slow_down, zoom_in).chain()
+                    .run_if(in_state(CinematicState::On))
+                    .run_if(resource_exists::<MinDistance>),
+                toggle_cinematic.run_if(action_just_pressed(Action::ToggleCinematic)),
+            ))
+            .add_systems(OnExit(CinematicState::On), restore);
+    }
+}
+
+/// `TimeDilation` the closest pair calls for, before easing.
+fn target_time_scale(min_distance: &MinDistance, config: &CinematicConfig) -> f64 {
+    let min_scale = config.min_time_scale.clamp(0., 1.);
+    match min_distance.0 {
+        Some(pair) if config.threshold > 0. => (pair.distance / config.threshold).clamp(min_scale, 1.),
+        _ => 1.,
+    }
+}
+
+fn slow_down(
+    time: Res<Time<Real>>,
+    config: Res<Config>,
+    min_distance: Res<MinDistance>,
+    mut dilation: ResMut<TimeDilation>,
+) {
+    let cinematic = &config.render.cinematic;
+    let target = target_time_scale(&min_distance, cinematic);
+    let tau = cinematic.smoothing.max(0.);
+    let blend = if tau > 0. { 1. - (-time.delta_seconds() / tau).exp() } else { 1. };
+    let scale = dilation.0 + (target - dilation.0) * f64::from(blend);
+    dilation.set_if_neq(TimeDilation(scale));
+}
+
+/// Zooms with the slowdown, keeping the closest pair's midpoint where it
+/// is on screen.
+#[allow(clippy::too_many_arguments)]
+fn zoom_in(
+    config: Res<Config>,
+    dilation: Res<TimeDilation>,
+    min_distance: Res<MinDistance>,
+    target: Res<State<CameraTarget>>,
+    origin: Res<RenderOrigin>,
+    positions: Query<&Position>,
+    mut applied: ResMut<CinematicZoom>,
+    mut camera: Query<(&mut Transform, &mut Projection), With<MainCamera>>,
+) {
+    let cinematic = &config.render.cinematic;
+    let min_scale = cinematic.min_time_scale.clamp(0., 1.);
+    // How far into slow motion we are, from 0 at full speed to 1 at the slowest.
+    let depth = if min_scale < 1. { ((1. - dilation.0) / (1. - min_scale)).clamp(0., 1.) as f32 } else { 0. };
+    let wanted = if *target.get() == CameraTarget::AutoFit {
+        1.
+    } else {
+        1. + (cinematic.zoom.max(1.) - 1.) * depth
+    };
+    let Ok((mut transform, mut projection)) = camera.get_single_mut() else {
+        return;
+    };
+    let Projection::Orthographic(projection) = &mut *projection else {
+        return;
+    };
+    // Ortho scale shrinks as the zoom grows.
+    let step = applied.0 / wanted;
+    if step == 1. {
+        return;
+    }
+    projection.scale *= step;
+    let pivot = min_distance.0
+        .and_then(|pair| Some((positions.get(pair.a).ok()?.0 + positions.get(pair.b).ok()?.0) / 2.))
+        .map(|midpoint| origin.to_render(midpoint).truncate());
+    if let Some(pivot) = pivot {
+        let position = pivot + (transform.translation.truncate() - pivot) * step;
+        transform.translation = position.extend(transform.translation.z);
+    }
+    applied.0 = wanted;
+}
+
+/// Back to full speed, and the zoom the user had.
+fn restore(
+    mut dilation: ResMut<TimeDilation>,
+    mut applied: ResMut<CinematicZoom>,
+    mut camera: Query<&mut Projection, With<MainCamera>>,
+) {
+    *dilation = TimeDilation::default();
+    if let Ok(mut projection) = camera.get_single_mut() {
+        if let Projection::Orthographic(projection) = &mut *projection {
+            projection.scale *= applied.0;
+        }
+    }
+    applied.0 = 1.;
+}
+
+fn toggle_cinematic(state: Res<State<CinematicState>>, mut next_state: ResMut<NextState<CinematicState>>) {
+    match state.get() {
+        CinematicState::On => next_state.set(CinematicState::Off),
+        CinematicState::Off => next_state.set(CinematicState::On),
+    }
+}
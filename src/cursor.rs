@@ -1,40 +1,149 @@
-use bevy::prelude::{App, Camera, Component, GlobalTransform, Plugin, Query, ResMut, Resource, Update, Vec2, Window, With};
+use bevy::input::touch::Touches;
+use bevy::input::InputSystem;
+use bevy::math::DVec2;
+use bevy::prelude::{
+    App, ButtonInput, Component, GlobalTransform, IntoSystemConfigs, KeyCode, Local, MouseButton, Plugin,
+    PreUpdate, Projection, Query, Res, ResMut, Resource, Update, Vec2, Vec3, Window, With,
+};
+use bevy::render::camera::CameraProjection;
 use bevy::window::PrimaryWindow;
+use crate::input::{Action, Binding, KeyBindings};
+use crate::simulation::RenderOrigin;
 
-/// We will store the world position of the mouse cursor here.
+/// We will store the world position of the mouse cursor here, in f64 like
+/// `Position` since the world may be far from the render origin.
 #[derive(Resource, Default)]
-pub struct CursorCoords(pub(crate) Vec2);
+pub struct CursorCoords(pub(crate) DVec2);
+
+impl CursorCoords {
+    /// World position under the pointer.
+    pub fn world(&self) -> DVec2 {
+        self.0
+    }
+}
 
 /// Used to help identify our main camera
 #[derive(Component)]
 pub struct MainCamera;
 
+/// Tracks the pointer in `CursorCoords`, from the mouse or a touch screen.
+///
+/// Multi-touch maps onto the single cursor: the earliest finger still down
+/// is the pointer. A finger landing on an empty screen also holds
+/// `Action::SpawnOrDrag`'s binding until it lifts, so a tap spawns or
+/// selects and a drag moves or launches a body exactly like the mouse
+/// does. Further fingers press nothing; two fingers pinch to zoom, see
+/// `crate::camera::CameraPlugin`. The cursor stays where the last finger
+/// lifted.
 pub struct CursorPlugin;
 
 impl Plugin for CursorPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(CursorCoords::default())
+            .init_resource::<RenderOrigin>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<Touches>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .add_systems(PreUpdate, press_with_first_finger.after(InputSystem))
             .add_systems(Update, my_cursor_system);
     }
 }
 
+/// Where the pointer is, in logical window pixels: the earliest finger
+/// still on the screen, else the mouse cursor.
+pub fn pointer_position(window: &Window, touches: &Touches) -> Option<Vec2> {
+    first_finger(touches)
+        .and_then(|id| touches.get_pressed(id))
+        .map(|touch| touch.position())
+        .or_else(|| window.cursor_position())
+}
+
+/// Id of the earliest finger still down. Ids count up as fingers land.
+fn first_finger(touches: &Touches) -> Option<u64> {
+    touches.iter().map(|touch| touch.id()).min()
+}
+
+/// Holds `Action::SpawnOrDrag`'s binding down while the pointer finger is.
+fn press_with_first_finger(
+    touches: Res<Touches>,
+    bindings: Res<KeyBindings>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut finger: Local<Option<u64>>,
+) {
+    let Some(binding) = bindings.get(Action::SpawnOrDrag) else {
+        return;
+    };
+    if let Some(id) = *finger {
+        if touches.get_pressed(id).is_some() {
+            return;
+        }
+        *finger = None;
+        match binding {
+            Binding::Key(key) => keys.release(key),
+            Binding::Mouse(button) => mouse.release(button),
+        }
+    }
+    // Only a finger on an otherwise empty screen becomes the pointer.
+    let mut down = touches.iter();
+    let (Some(touch), None) = (down.next(), down.next()) else {
+        return;
+    };
+    if !touches.just_pressed(touch.id()) {
+        return;
+    }
+    *finger = Some(touch.id());
+    match binding {
+        Binding::Key(key) => keys.press(key),
+        Binding::Mouse(button) => mouse.press(button),
+    }
+}
+
 fn my_cursor_system(
     mut world_coordinates: ResMut<CursorCoords>,
     // query to get the window (so we can read the current cursor position)
     q_window: Query<&Window, With<PrimaryWindow>>,
     // query to get camera transform
-    q_camera: Query<(Option<&Camera>, Option<&GlobalTransform>), With<MainCamera>>,
+    q_camera: Query<(&GlobalTransform, &Projection), With<MainCamera>>,
+    origin: Res<RenderOrigin>,
+    touches: Res<Touches>,
 ) {
-    // get the camera info and transform
-    // assuming there is exactly one main camera entity, so Query::single() is OK
-    if let (Some(camera), Some(camera_transform)) = q_camera.single() {
-        // check if the cursor is inside the window and get its position
-        // then, ask bevy to convert into world coordinates, and truncate to discard Z
-        if let Some(world_position) = q_window.single().cursor_position()
-            .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
-            .map(|ray| ray.origin.truncate())
-        {
-            world_coordinates.0 = world_position;
-        }
+    let (Ok(window), Ok((camera_transform, projection))) = (q_window.get_single(), q_camera.get_single()) else {
+        return;
+    };
+    // check if the pointer is inside the window and get its position
+    // then convert it into world coordinates, and truncate to discard Z
+    if let Some(world_position) = pointer_position(window, &touches)
+        .and_then(|cursor| cursor_to_render(window, camera_transform, projection, cursor))
+        .map(|point| origin.to_world(point).truncate())
+    {
+        world_coordinates.0 = world_position;
     }
-}
\ No newline at end of file
+}
+
+/// The near-plane point under `cursor`, in render space.
+///
+/// Like `Camera::viewport_to_world`, but sized from the window as it is
+/// now: the camera's computed viewport and projection only catch up with
+/// a resize in `PostUpdate`, so for the frame after one they would map
+/// the cursor against the old size.
+fn cursor_to_render(
+    window: &Window,
+    camera_transform: &GlobalTransform,
+    projection: &Projection,
+    cursor: Vec2,
+) -> Option<Vec3> {
+    let size = window.size();
+    if size.x <= 0. || size.y <= 0. {
+        return None;
+    }
+    let mut projection = projection.clone();
+    projection.update(size.x, size.y);
+    let mut ndc = cursor / size * 2. - Vec2::ONE;
+    ndc.y = -ndc.y;
+    let ndc_to_world = camera_transform.compute_matrix() * projection.get_clip_from_view().inverse();
+    // Bevy's reversed depth puts the near plane at NDC z = 1.
+    let point = ndc_to_world.project_point3(ndc.extend(1.));
+    (!point.is_nan()).then_some(point)
+}
@@ -0,0 +1,251 @@
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use serde::{Deserialize, Serialize};
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::{run_physics_substeps, Body, Config, Position, RenderOrigin, SimulationState};
+
+/// Depth of the heatmap plane: behind the bodies and their trails, in front
+/// of the starfield.
+const DENSITY_DEPTH: f32 = -100.;
+
+/// Whether the heatmap is accumulated and drawn.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum DensityState {
+    #[default]
+    Hide,
+    Show,
+}
+
+/// Layout and color of the heatmap, see `RenderConfig::density`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DensityConfig {
+    /// World XY position the grid is centered on.
+    pub center: DVec2,
+    /// Side of one cell, in scenario units.
+    pub cell_size: f64,
+    /// Cells along each side of the square grid.
+    pub cells: u32,
+    /// Color of the busiest cell; the rest fade from it towards transparent.
+    pub color: LinearRgba,
+}
+
+impl Default for DensityConfig {
+    fn default() -> Self {
+        Self {
+            center: DVec2::ZERO,
+            cell_size: 0.5,
+            cells: 256,
+            color: LinearRgba::rgb(1., 0.45, 0.1),
+        }
+    }
+}
+
+/// How many physics ticks a body spent over each cell of a square grid in
+/// the XY plane. Positions outside the grid are not counted.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct DensityMap {
+    center: DVec2,
+    cell_size: f64,
+    cells: u32,
+    counts: Vec<u32>,
+    peak: u32,
+}
+
+impl DensityMap {
+    pub fn new(config: &DensityConfig) -> Self {
+        let cells = config.cells.max(1);
+        Self {
+            center: config.center,
+            cell_size: config.cell_size,
+            cells,
+            counts: vec![0; (cells * cells) as usize],
+            peak: 0,
+        }
+    }
+
+    /// Index of the cell under `position`, row by row from -Y.
+    pub fn cell_at(&self, position: DVec2) -> Option<usize> {
+        if self.cell_size <= 0. {
+            return None;
+        }
+        let half = f64::from(self.cells) / 2.;
+        let cell = ((position - self.center) / self.cell_size + half).floor();
+        let in_range = |v: f64| (0. ..f64::from(self.cells)).contains(&v);
+        (in_range(cell.x) && in_range(cell.y))
+            .then(|| cell.y as usize * self.cells as usize + cell.x as usize)
+    }
+
+    /// Counts one tick over the cell under `position`.
+    pub fn record(&mut self, position: DVec2) {
+        if let Some(cell) = self.cell_at(position) {
+            self.counts[cell] = self.counts[cell].saturating_add(1);
+            self.peak = self.peak.max(self.counts[cell]);
+        }
+    }
+
+    /// Ticks counted over the cell under `position`.
+    pub fn count_at(&self, position: DVec2) -> u32 {
+        self.cell_at(position).map_or(0, |cell| self.counts[cell])
+    }
+
+    /// The highest count of any cell.
+    pub fn peak(&self) -> u32 {
+        self.peak
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.peak = 0;
+    }
+
+    /// Side of the whole grid, in scenario units.
+    fn extent(&self) -> f64 {
+        f64::from(self.cells) * self.cell_size
+    }
+}
+
+/// The plane showing `DensityMap` through a texture with one texel per cell.
+#[derive(Component)]
+struct DensityOverlay;
+
+#[derive(Resource)]
+struct DensityTexture(Handle<Image>);
+
+/// Accumulates a `DensityMap` of where bodies spend their time, drawn as
+/// a glowing overlay behind them that builds up while the simulation runs.
+/// Counts only accumulate while it is shown, are kept while it is hidden,
+/// and are reset with `Action::ClearDensity`.
+pub struct DensityPlugin;
+
+impl Plugin for DensityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<DensityState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<RenderOrigin>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                FixedUpdate,
+                accumulate
+                    .after(run_physics_substeps)
+                    .run_if(in_state(SimulationState::Running))
+                    .run_if(in_state(DensityState::Show)),
+            )
+            .add_systems(Update, (
+                (
+                    paint.run_if(resource_changed::<DensityMap>),
+                    follow_origin,
+                ).run_if(in_state(DensityState::Show)),
+                toggle_density.run_if(action_just_pressed(Action::ToggleDensity)),
+                clear_density.run_if(action_just_pressed(Action::ClearDensity)),
+            ))
+            .add_systems(OnEnter(DensityState::Show), set_overlay_visibility(Visibility::Inherited))
+            .add_systems(OnExit(DensityState::Show), set_overlay_visibility(Visibility::Hidden));
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    config: Res<Config>,
+) {
+    let map = DensityMap::new(&config.render.density);
+    let image = images.add(Image::new_fill(
+        Extent3d { width: map.cells, height: map.cells, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+    let extent = map.extent() as f32;
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Rectangle::new(extent, extent)),
+            material: materials.add(StandardMaterial {
+                base_color_texture: Some(image.clone()),
+                unlit: true,
+                alpha_mode: AlphaMode::Add,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        DensityOverlay,
+    ));
+    commands.insert_resource(DensityTexture(image));
+    commands.insert_resource(map);
+}
+
+fn accumulate(mut map: ResMut<DensityMap>, bodies: Query<&Position, With<Body>>) {
+    for position in bodies.iter() {
+        map.record(position.0.truncate());
+    }
+}
+
+/// Rewrites the texture from the counts, on a log scale so cells visited
+/// only a few times still show next to the busiest one.
+fn paint(
+    map: Res<DensityMap>,
+    texture: Res<DensityTexture>,
+    config: Res<Config>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(image) = images.get_mut(&texture.0) else {
+        return;
+    };
+    let color = Srgba::from(config.render.density.color);
+    let peak = f64::from(map.peak.max(1)).ln_1p();
+    let cells = map.cells as usize;
+    for (cell, count) in map.counts.iter().enumerate() {
+        let t = (f64::from(*count).ln_1p() / peak) as f32;
+        // Texture rows run top to bottom, grid rows from -Y up.
+        let (x, y) = (cell % cells, cells - 1 - cell / cells);
+        let texel = (y * cells + x) * 4;
+        let Some(pixel) = image.data.get_mut(texel..texel + 4) else {
+            continue;
+        };
+        pixel.copy_from_slice(&[
+            (color.red * t * 255.) as u8,
+            (color.green * t * 255.) as u8,
+            (color.blue * t * 255.) as u8,
+            (t * 255.) as u8,
+        ]);
+    }
+}
+
+/// Keeps the plane on the grid's world position as `RenderOrigin` moves.
+fn follow_origin(
+    map: Res<DensityMap>,
+    origin: Res<RenderOrigin>,
+    mut overlay: Query<&mut Transform, With<DensityOverlay>>,
+) {
+    let translation = origin.to_render(map.center.extend(0.)).truncate().extend(DENSITY_DEPTH);
+    for mut transform in overlay.iter_mut() {
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
+
+fn set_overlay_visibility(visibility: Visibility) -> impl FnMut(Query<&mut Visibility, With<DensityOverlay>>) {
+    move |mut query| {
+        for mut overlay in query.iter_mut() {
+            *overlay = visibility;
+        }
+    }
+}
+
+fn toggle_density(state: Res<State<DensityState>>, mut next_state: ResMut<NextState<DensityState>>) {
+    match state.get() {
+        DensityState::Show => next_state.set(DensityState::Hide),
+        DensityState::Hide => next_state.set(DensityState::Show),
+    }
+}
+
+fn clear_density(mut map: ResMut<DensityMap>) {
+    map.clear();
+}
@@ -0,0 +1,213 @@
+use std::fmt;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use crate::simulation::{run_physics_substeps, Body, Config, Mass, MergeCount, Position, SimClock, TestParticle, Velocity};
+
+/// Total angular momentum `L = Σ mᵢ (rᵢ - r_com) × (vᵢ - v_com)` of the
+/// bodies that exert gravity, about their center of mass. Gravity alone
+/// conserves it, so drift in `L` measures integrator error. Test particles
+/// are left out since the system never pulls back on them.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct AngularMomentum(pub DVec3);
+
+impl AngularMomentum {
+    /// `L` of `(mass, position, velocity)` states about their center of mass.
+    pub fn from_states(bodies: &[(f64, DVec3, DVec3)]) -> Self {
+        let total_mass: f64 = bodies.iter().map(|(m, _, _)| m).sum();
+        if total_mass <= 0.0 {
+            return Self(DVec3::ZERO);
+        }
+        let com = bodies.iter().map(|(m, r, _)| *m * *r).sum::<DVec3>() / total_mass;
+        let com_velocity = bodies.iter().map(|(m, _, v)| *m * *v).sum::<DVec3>() / total_mass;
+        Self(bodies.iter()
+            .map(|(m, r, v)| *m * (*r - com).cross(*v - com_velocity))
+            .sum())
+    }
+}
+
+/// Newtonian energy `Σ ½ mᵢ vᵢ² - Σ G mᵢ mⱼ / rᵢⱼ` of `(mass, position,
+/// velocity)` states. Only conserved under the default point-mass
+/// inverse-square law (no `gravity_cutoff`, `extended_bodies` or other
+/// `force_exponent`), which it assumes; drift still compares runs.
+pub fn total_energy(bodies: &[(f64, DVec3, DVec3)], g: f64) -> f64 {
+    let kinetic: f64 = bodies.iter().map(|(m, _, v)| 0.5 * m * v.length_squared()).sum();
+    let mut potential = 0.;
+    for (i, (mi, ri, _)) in bodies.iter().enumerate() {
+        for (mj, rj, _) in &bodies[i + 1..] {
+            let distance = ri.distance(*rj);
+            if distance > 0. {
+                potential -= g * mi * mj / distance;
+            }
+        }
+    }
+    kinetic + potential
+}
+
+/// Whether each of `bodies`, given as `(mass, position, velocity)`, is
+/// leaving the rest for good: it moves away from their center of mass with
+/// at least escape speed for their combined mass plus its own. The rest's
+/// totals are everyone's minus the body's own, so this is one pass however
+/// many bodies there are. Give bodies that exert no gravity zero mass.
+pub(crate) fn escaping(bodies: &[(f64, DVec3, DVec3)], g: f64) -> Vec<bool> {
+    let total_mass: f64 = bodies.iter().map(|(m, _, _)| m).sum();
+    let total_moment: DVec3 = bodies.iter().map(|(m, r, _)| *m * *r).sum();
+    let total_momentum: DVec3 = bodies.iter().map(|(m, _, v)| *m * *v).sum();
+    bodies.iter()
+        .map(|&(mass, position, velocity)| {
+            let others = total_mass - mass;
+            if others <= 0. {
+                return false;
+            }
+            let com = (total_moment - mass * position) / others;
+            let com_velocity = (total_momentum - mass * velocity) / others;
+            let (r, v) = (position - com, velocity - com_velocity);
+            r.dot(v) > 0. && 0.5 * v.length_squared() >= g * (others + mass.max(0.)) / r.length()
+        })
+        .collect()
+}
+
+/// Outcome of a headless run, as returned by `crate::simulation::run_steps`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunSummary {
+    /// Physics ticks the run advanced.
+    pub steps: u64,
+    /// Simulated seconds those ticks covered.
+    pub simulated_time: f64,
+    /// Bodies left at the end, test particles included.
+    pub bodies: usize,
+    /// Bodies absorbed into others during the run, by
+    /// `Config::gw_inspiral`. Bodies evicted by `Config::max_bodies` or
+    /// despawned otherwise don't count.
+    pub merged: usize,
+    /// Bodies at the end that are unbound and receding from the rest.
+    pub escaping: usize,
+    /// `total_energy` of the bodies that exert gravity, before and after.
+    pub initial_energy: f64,
+    pub final_energy: f64,
+}
+
+impl RunSummary {
+    /// Relative change in total energy over the run, in percent. Zero when
+    /// neither end had any energy, e.g. with no bodies.
+    pub fn energy_drift_percent(&self) -> f64 {
+        if self.initial_energy == 0. && self.final_energy == 0. {
+            return 0.;
+        }
+        (self.final_energy - self.initial_energy) / self.initial_energy.abs() * 100.
+    }
+}
+
+impl fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} steps, {:.4} s simulated: {} bodies left, {} merged, {} escaping, \
+            energy {:.6e} -> {:.6e} ({:+.4}%)",
+            self.steps, self.simulated_time, self.bodies, self.merged, self.escaping,
+            self.initial_energy, self.final_energy, self.energy_drift_percent(),
+        )
+    }
+}
+
+/// The state a `RunSummary` is measured against.
+pub(crate) struct RunStart {
+    clock: SimClock,
+    merges: u64,
+    energy: f64,
+}
+
+impl RunStart {
+    pub(crate) fn capture(world: &mut World) -> Self {
+        let g = world.resource::<Config>().gravitational_constant();
+        Self {
+            clock: *world.resource::<SimClock>(),
+            merges: world.get_resource::<MergeCount>().map_or(0, |merges| merges.0),
+            energy: total_energy(&attractor_states(world), g),
+        }
+    }
+
+    pub(crate) fn finish(self, world: &mut World) -> RunSummary {
+        let g = world.resource::<Config>().gravitational_constant();
+        let clock = *world.resource::<SimClock>();
+        let attractors = attractor_states(world);
+        let bodies: Vec<_> = world
+            .query_filtered::<(&Mass, &Position, &Velocity, Has<TestParticle>), With<Body>>()
+            .iter(world)
+            .map(|(m, p, v, test_particle)| (if test_particle { 0. } else { m.0 }, p.0, v.0))
+            .collect();
+        let escaping = escaping(&bodies, g).into_iter().filter(|escaping| *escaping).count();
+        RunSummary {
+            steps: clock.steps - self.clock.steps,
+            simulated_time: clock.elapsed - self.clock.elapsed,
+            bodies: bodies.len(),
+            merged: (world.get_resource::<MergeCount>().map_or(0, |merges| merges.0) - self.merges) as usize,
+            escaping,
+            initial_energy: self.energy,
+            final_energy: total_energy(&attractors, g),
+        }
+    }
+}
+
+fn attractor_states(world: &mut World) -> Vec<(f64, DVec3, DVec3)> {
+    world
+        .query_filtered::<(&Mass, &Position, &Velocity), (With<Body>, Without<TestParticle>)>()
+        .iter(world)
+        .map(|(m, p, v)| (m.0, p.0, v.0))
+        .collect()
+}
+
+/// Mass-weighted mean position of the bodies that exert gravity. `None`
+/// when there are none, or their masses don't sum to a positive total.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct CenterOfMass(pub Option<DVec3>);
+
+/// The two bodies whose centers are closest, updated every physics tick.
+/// `None` with fewer than two bodies.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct MinDistance(pub Option<ClosestPair>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosestPair {
+    pub a: Entity,
+    pub b: Entity,
+    /// Center-to-center distance.
+    pub distance: f64,
+}
+
+pub struct DiagnosticsPlugin;
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AngularMomentum>()
+            .init_resource::<CenterOfMass>()
+            .init_resource::<MinDistance>()
+            .add_systems(Update, (update_angular_momentum, update_center_of_mass))
+            .add_systems(FixedUpdate, update_min_distance.after(run_physics_substeps));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_angular_momentum(
+    mut momentum: ResMut<AngularMomentum>,
+    query: Query<(&Mass, &Position, &Velocity), (With<Body>, Without<TestParticle>)>,
+) {
+    let bodies: Vec<_> = query.iter().map(|(m, p, v)| (m.0, p.0, v.0)).collect();
+    momentum.set_if_neq(AngularMomentum::from_states(&bodies));
+}
+
+#[allow(clippy::type_complexity)]
+fn update_center_of_mass(
+    mut center: ResMut<CenterOfMass>,
+    query: Query<(&Mass, &Position), (With<Body>, Without<TestParticle>)>,
+) {
+    let total_mass: f64 = query.iter().map(|(m, _)| m.0).sum();
+    let weighted: DVec3 = query.iter().map(|(m, p)| m.0 * p.0).sum();
+    center.set_if_neq(CenterOfMass((total_mass > 0.).then(|| weighted / total_mass)));
+}
+
+fn update_min_distance(mut min_distance: ResMut<MinDistance>, query: Query<(Entity, &Position), With<Body>>) {
+    let closest = query.iter_combinations()
+        .map(|[(a, pa), (b, pb)]| ClosestPair { a, b, distance: pa.0.distance(pb.0) })
+        .min_by(|x, y| x.distance.total_cmp(&y.distance));
+    min_distance.set_if_neq(MinDistance(closest));
+}
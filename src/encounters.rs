@@ -0,0 +1,78 @@
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use crate::simulation::{run_physics_substeps, Body, BodyConfig, Config, Position, SimulationState};
+use crate::spatial::SpatialGrid;
+
+/// Fired when the gap between two bodies' surfaces first drops below
+/// `Config::close_approach_distance`. A negative gap means they overlap.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CloseApproach {
+    pub a: Entity,
+    pub b: Entity,
+    pub gap: f64,
+}
+
+/// Pairs currently within the close-approach distance, so each encounter
+/// is only reported once.
+#[derive(Resource, Default)]
+struct ActiveEncounters(HashSet<(Entity, Entity)>);
+
+pub struct EncounterPlugin;
+
+impl Plugin for EncounterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CloseApproach>()
+            .init_resource::<ActiveEncounters>()
+            .add_systems(
+                FixedUpdate,
+                detect_close_approaches
+                    .after(run_physics_substeps)
+                    .run_if(in_state(SimulationState::Running))
+                    .run_if(|config: Res<Config>| config.close_approach_distance.is_some()),
+            );
+    }
+}
+
+fn detect_close_approaches(
+    config: Res<Config>,
+    query: Query<(Entity, &Position, &BodyConfig), With<Body>>,
+    mut active: ResMut<ActiveEncounters>,
+    mut events: EventWriter<CloseApproach>,
+) {
+    let Some(threshold) = config.close_approach_distance else {
+        return;
+    };
+    let bodies: Vec<(Entity, DVec3, f64)> = query.iter()
+        .map(|(entity, position, body)| (entity, position.0, body.radius))
+        .collect();
+    let max_radius = bodies.iter().map(|(_, _, radius)| *radius).fold(0.0, f64::max);
+    // Any pair within the threshold has centers at most this far apart.
+    let reach = 2.0 * max_radius + threshold;
+    // Cells far smaller than `reach` make every cell scan a huge block of
+    // neighbours, so the configured size is floored at an eighth of it.
+    let cell_size = config.spatial_cell_size
+        .filter(|size| *size > 0.)
+        .unwrap_or(reach)
+        .max(reach / 8.)
+        .max(f64::EPSILON);
+
+    let positions: Vec<DVec3> = bodies.iter().map(|(_, position, _)| *position).collect();
+    let grid = SpatialGrid::from_points(cell_size, &positions);
+
+    let mut close = HashSet::default();
+    for (i, j) in grid.candidate_pairs(reach) {
+        let (a, pa, ra) = bodies[i];
+        let (b, pb, rb) = bodies[j];
+        let gap = pa.distance(pb) - ra - rb;
+        if gap >= threshold {
+            continue;
+        }
+        let pair = if a < b { (a, b) } else { (b, a) };
+        if !active.0.contains(&pair) {
+            events.send(CloseApproach { a: pair.0, b: pair.1, gap });
+        }
+        close.insert(pair);
+    }
+    active.0 = close;
+}
@@ -0,0 +1,110 @@
+use std::fmt::Write;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+use bevy::prelude::*;
+use crate::cursor::MainCamera;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::trail::{Trail, TrailColor};
+
+/// Stroke width of exported trails, in SVG user units (logical pixels).
+const STROKE_WIDTH: f32 = 1.5;
+
+/// A trail projected into viewport space, ready to write out.
+pub struct SvgPath {
+    pub points: Vec<Vec2>,
+    /// One color for the whole path, or one per point.
+    pub colors: Vec<LinearRgba>,
+}
+
+pub struct ExportPlugin;
+
+impl Plugin for ExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyBindings>()
+            .add_systems(Update, export_trails_svg.run_if(action_just_pressed(Action::ExportTrailsSvg)));
+    }
+}
+
+/// Renders `paths` as an SVG document of `size` logical pixels.
+///
+/// Coordinates are viewport coordinates as returned by
+/// `Camera::world_to_viewport`: origin in the top-left corner, y pointing
+/// down, one unit per logical pixel. Under the orthographic camera this is a
+/// uniform scale and offset of world X/Y, with depth discarded.
+pub fn trails_to_svg(size: Vec2, paths: &[SvgPath]) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"black\"/>\n",
+        w = size.x,
+        h = size.y,
+    );
+    for path in paths.iter().filter(|path| path.points.len() >= 2) {
+        if let [color] = path.colors[..] {
+            let points = path.points.iter()
+                .map(|p| format!("{:.2},{:.2}", p.x, p.y))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(
+                svg,
+                "<polyline points=\"{points}\" fill=\"none\" {}/>",
+                stroke(color),
+            );
+        } else {
+            // Per-point colors: one segment per pair, colored by its start.
+            for (segment, color) in path.points.windows(2).zip(path.colors.iter()) {
+                let _ = writeln!(
+                    svg,
+                    "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" {}/>",
+                    segment[0].x, segment[0].y, segment[1].x, segment[1].y,
+                    stroke(*color),
+                );
+            }
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// SVG stroke attributes for an HDR color, scaled back into displayable range.
+fn stroke(color: LinearRgba) -> String {
+    let peak = color.red.max(color.green).max(color.blue);
+    let color = if peak > 1. {
+        LinearRgba::new(color.red / peak, color.green / peak, color.blue / peak, color.alpha)
+    } else {
+        color
+    };
+    format!(
+        "stroke=\"{}\" stroke-opacity=\"{:.2}\" stroke-width=\"{STROKE_WIDTH}\" stroke-linejoin=\"round\"",
+        Srgba::from(color).with_alpha(1.).to_hex(),
+        color.alpha,
+    )
+}
+
+fn export_trails_svg(
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    trails: Query<(&Trail, &TrailColor)>,
+) {
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let Some(size) = camera.logical_viewport_size() else {
+        return;
+    };
+    let paths: Vec<SvgPath> = trails.iter()
+        .map(|(trail, color)| SvgPath {
+            points: trail.points.iter()
+                .filter_map(|p| camera.world_to_viewport(camera_transform, *p))
+                .collect(),
+            colors: trail.vertex_colors()
+                .map(|colors| colors.into_iter().map(LinearRgba::from_f32_array).collect())
+                .unwrap_or_else(|| vec![color.0.into()]),
+        })
+        .collect();
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let path = format!("trails-{timestamp}.svg");
+    match fs::write(&path, trails_to_svg(size, &paths)) {
+        Ok(()) => info!("exported trails to {path}"),
+        Err(err) => error!("failed to export trails to {path}: {err}"),
+    }
+}
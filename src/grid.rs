@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+use crate::cursor::MainCamera;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::RenderOrigin;
+
+/// Roughly how many gridlines span the shorter side of the view.
+const TARGET_LINES: f32 = 8.;
+
+const GRID_COLOR: Color = Color::srgba(1., 1., 1., 0.08);
+
+/// Whether the world-space grid and its distance labels are drawn.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum GridState {
+    #[default]
+    Hide,
+    Show,
+}
+
+/// A gridline label. Spawned on demand and reused between frames, hidden
+/// when fewer lines are visible.
+#[derive(Component)]
+struct GridLabel;
+
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GridState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<RenderOrigin>()
+            .add_systems(Update, (
+                draw_grid.run_if(in_state(GridState::Show)),
+                toggle_grid.run_if(action_just_pressed(Action::ToggleGrid)),
+            ))
+            .add_systems(OnExit(GridState::Show), hide_labels);
+    }
+}
+
+/// The smallest of 1, 2 or 5 times a power of ten that is at least `raw`.
+pub fn nice_step(raw: f32) -> f32 {
+    let magnitude = 10f32.powf(raw.log10().floor());
+    [1., 2., 5., 10.].into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10. * magnitude)
+}
+
+/// Formats a gridline coordinate with just enough decimals for `step`.
+fn format_distance(value: f64, step: f32) -> String {
+    let decimals = (-step.log10().floor()).max(0.) as usize;
+    // Adding zero turns -0.0 into 0.0.
+    format!("{:.decimals$}", value + 0.)
+}
+
+fn draw_grid(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    origin: Res<RenderOrigin>,
+    mut labels: Query<(&mut Text, &mut Style, &mut Visibility), With<GridLabel>>,
+) {
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let Some(size) = camera.logical_viewport_size() else {
+        return;
+    };
+    // Viewport y points down, so the top-left corner maps to (min.x, max.y).
+    let (Some(top_left), Some(bottom_right)) = (
+        camera.viewport_to_world_2d(camera_transform, Vec2::ZERO),
+        camera.viewport_to_world_2d(camera_transform, size),
+    ) else {
+        return;
+    };
+    let min = top_left.min(bottom_right);
+    let max = top_left.max(bottom_right);
+    let extent = max - min;
+    if extent.min_element() <= 0. || !extent.is_finite() {
+        return;
+    }
+    let step = nice_step(extent.min_element() / TARGET_LINES);
+
+    // Lines sit on world multiples of `step`, drawn relative to the render origin.
+    let world_step = f64::from(step);
+    let world_min = origin.0.truncate() + min.as_dvec2();
+    let world_max = origin.0.truncate() + max.as_dvec2();
+
+    // Labels in viewport pixels: x values along the bottom, y values along the left.
+    let mut wanted: Vec<(String, Vec2)> = vec![];
+    for i in (world_min.x / world_step).ceil() as i64..=(world_max.x / world_step).floor() as i64 {
+        let world_x = i as f64 * world_step;
+        let x = (world_x - origin.0.x) as f32;
+        gizmos.line(Vec3::new(x, min.y, 0.), Vec3::new(x, max.y, 0.), GRID_COLOR);
+        if let Some(at) = camera.world_to_viewport(camera_transform, Vec3::new(x, min.y, 0.)) {
+            wanted.push((format_distance(world_x, step), Vec2::new(at.x + 3., size.y - 20.)));
+        }
+    }
+    for i in (world_min.y / world_step).ceil() as i64..=(world_max.y / world_step).floor() as i64 {
+        let world_y = i as f64 * world_step;
+        let y = (world_y - origin.0.y) as f32;
+        gizmos.line(Vec3::new(min.x, y, 0.), Vec3::new(max.x, y, 0.), GRID_COLOR);
+        if let Some(at) = camera.world_to_viewport(camera_transform, Vec3::new(min.x, y, 0.)) {
+            wanted.push((format_distance(world_y, step), Vec2::new(4., at.y - 18.)));
+        }
+    }
+
+    let mut wanted = wanted.into_iter();
+    for (mut text, mut style, mut visibility) in labels.iter_mut() {
+        match wanted.next() {
+            Some((value, at)) => {
+                text.sections[0].value = value;
+                style.left = Val::Px(at.x);
+                style.top = Val::Px(at.y);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+    for (value, at) in wanted {
+        commands.spawn((
+            TextBundle::from_section(value, TextStyle {
+                font_size: 14.,
+                color: Color::srgba(1., 1., 1., 0.4),
+                ..default()
+            }).with_style(Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(at.x),
+                top: Val::Px(at.y),
+                ..default()
+            }),
+            GridLabel,
+        ));
+    }
+}
+
+fn hide_labels(mut labels: Query<&mut Visibility, With<GridLabel>>) {
+    for mut visibility in labels.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn toggle_grid(state: Res<State<GridState>>, mut next_state: ResMut<NextState<GridState>>) {
+    match state.get() {
+        GridState::Show => next_state.set(GridState::Hide),
+        GridState::Hide => next_state.set(GridState::Show),
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Everything the user can trigger from the keyboard or mouse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    ToggleSimulation,
+    ToggleTrails,
+    ToggleKeplerOverlay,
+    ToggleHelp,
+    /// Select the body under the cursor (dragging it while paused), or
+    /// spawn one if there is none.
+    SpawnOrDrag,
+    IncreaseMass,
+    DecreaseMass,
+    NextScenario,
+    PreviousScenario,
+    ExportTrailsSvg,
+    ToggleSpin,
+    ToggleRecording,
+    ReplayRecording,
+    ToggleGrid,
+    ToggleCursorReadout,
+    CycleCameraTarget,
+    PanCamera,
+    ToggleProfiling,
+    CycleVectors,
+    StoreCameraBookmark,
+    CycleBodyColor,
+    ToggleTrailColor,
+    SaveSnapshot,
+    RestoreSnapshot,
+    NudgeLeft,
+    NudgeRight,
+    NudgeUp,
+    NudgeDown,
+    NudgeOut,
+    NudgeIn,
+    ToggleIdLabels,
+    SpawnAtCenterOfMass,
+    ToggleCinematic,
+    ToggleDensity,
+    ClearDensity,
+    CancelLaunch,
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::ToggleSimulation => "pause / resume",
+            Action::ToggleTrails => "cycle trails: live / frozen / hidden",
+            Action::ToggleKeplerOverlay => "show / hide two-body Kepler orbit",
+            Action::ToggleHelp => "show / hide this help",
+            Action::SpawnOrDrag => "select a body or spawn one; drag while paused",
+            Action::IncreaseMass => "increase selected body's mass",
+            Action::DecreaseMass => "decrease selected body's mass",
+            Action::NextScenario => "next scenario in the library",
+            Action::PreviousScenario => "previous scenario in the library",
+            Action::ExportTrailsSvg => "export trails as SVG",
+            Action::ToggleSpin => "toggle body spin",
+            Action::ToggleRecording => "start or stop (and save) a recording",
+            Action::ReplayRecording => "replay the saved recording",
+            Action::ToggleGrid => "toggle distance grid",
+            Action::ToggleCursorReadout => "toggle cursor coordinate readout",
+            Action::CycleCameraTarget => "cycle camera target (free / center of mass / selected / fit all)",
+            Action::PanCamera => "drag to pan the camera",
+            Action::ToggleProfiling => "show / hide per-frame system timings",
+            Action::CycleVectors => "cycle acceleration / velocity arrows",
+            Action::StoreCameraBookmark => "hold with 1-9 to store a camera bookmark; 1-9 alone jumps back",
+            Action::CycleBodyColor => "shift the selected body's hue",
+            Action::ToggleTrailColor => "give the selected body's trail a contrasting color, or match it again",
+            Action::SaveSnapshot => "remember the whole simulation state",
+            Action::RestoreSnapshot => "return to the remembered state",
+            Action::NudgeLeft => "nudge the selected body's velocity along -x while paused",
+            Action::NudgeRight => "nudge the selected body's velocity along +x while paused",
+            Action::NudgeUp => "nudge the selected body's velocity along +y while paused",
+            Action::NudgeDown => "nudge the selected body's velocity along -y while paused",
+            Action::NudgeOut => "nudge the selected body's velocity along +z while paused",
+            Action::NudgeIn => "nudge the selected body's velocity along -z while paused",
+            Action::ToggleIdLabels => "show / hide body ids",
+            Action::SpawnAtCenterOfMass => "spawn a body at the center of mass",
+            Action::ToggleCinematic => "toggle slow motion on close approaches",
+            Action::ToggleDensity => "show / hide the position density heatmap",
+            Action::ClearDensity => "clear the density heatmap",
+            Action::CancelLaunch => "drop the body being aimed by click-drag",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// Human readable name, e.g. `T` or `Left mouse`.
+    pub fn label(self) -> String {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                name.strip_prefix("Key").map(str::to_string).unwrap_or(name)
+            }
+            Binding::Mouse(button) => format!("{button:?} mouse"),
+        }
+    }
+}
+
+/// Maps actions to keys or mouse buttons. Input systems and the help panel
+/// both read from here so they can't drift apart. Insert a customized copy
+/// before adding the plugins, or mutate it at runtime, to remap controls.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct KeyBindings(Vec<(Action, Binding)>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(vec![
+            (Action::ToggleSimulation, Binding::Key(KeyCode::Space)),
+            (Action::ToggleTrails, Binding::Key(KeyCode::KeyT)),
+            (Action::ToggleKeplerOverlay, Binding::Key(KeyCode::KeyK)),
+            (Action::ToggleHelp, Binding::Key(KeyCode::KeyH)),
+            (Action::SpawnOrDrag, Binding::Mouse(MouseButton::Left)),
+            (Action::IncreaseMass, Binding::Key(KeyCode::Equal)),
+            (Action::DecreaseMass, Binding::Key(KeyCode::Minus)),
+            (Action::NextScenario, Binding::Key(KeyCode::PageDown)),
+            (Action::PreviousScenario, Binding::Key(KeyCode::PageUp)),
+            (Action::ExportTrailsSvg, Binding::Key(KeyCode::KeyX)),
+            (Action::ToggleSpin, Binding::Key(KeyCode::KeyR)),
+            (Action::ToggleRecording, Binding::Key(KeyCode::KeyC)),
+            (Action::ReplayRecording, Binding::Key(KeyCode::KeyV)),
+            (Action::ToggleGrid, Binding::Key(KeyCode::KeyG)),
+            (Action::ToggleCursorReadout, Binding::Key(KeyCode::KeyI)),
+            (Action::CycleCameraTarget, Binding::Key(KeyCode::KeyF)),
+            (Action::PanCamera, Binding::Mouse(MouseButton::Middle)),
+            (Action::ToggleProfiling, Binding::Key(KeyCode::KeyP)),
+            (Action::CycleVectors, Binding::Key(KeyCode::KeyA)),
+            (Action::StoreCameraBookmark, Binding::Key(KeyCode::ControlLeft)),
+            (Action::CycleBodyColor, Binding::Key(KeyCode::KeyO)),
+            (Action::ToggleTrailColor, Binding::Key(KeyCode::KeyL)),
+            (Action::SaveSnapshot, Binding::Key(KeyCode::F5)),
+            (Action::RestoreSnapshot, Binding::Key(KeyCode::F9)),
+            (Action::NudgeLeft, Binding::Key(KeyCode::ArrowLeft)),
+            (Action::NudgeRight, Binding::Key(KeyCode::ArrowRight)),
+            (Action::NudgeUp, Binding::Key(KeyCode::ArrowUp)),
+            (Action::NudgeDown, Binding::Key(KeyCode::ArrowDown)),
+            (Action::NudgeOut, Binding::Key(KeyCode::Home)),
+            (Action::NudgeIn, Binding::Key(KeyCode::End)),
+            (Action::ToggleIdLabels, Binding::Key(KeyCode::KeyN)),
+            (Action::SpawnAtCenterOfMass, Binding::Key(KeyCode::KeyM)),
+            (Action::ToggleCinematic, Binding::Key(KeyCode::KeyB)),
+            (Action::ToggleDensity, Binding::Key(KeyCode::KeyD)),
+            (Action::ClearDensity, Binding::Key(KeyCode::Backspace)),
+            (Action::CancelLaunch, Binding::Key(KeyCode::Escape)),
+        ])
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: Action) -> Option<Binding> {
+        self.0.iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, binding)| *binding)
+    }
+
+    /// Binds `action` to `binding`, replacing its previous binding.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        match self.0.iter_mut().find(|(bound, _)| *bound == action) {
+            Some((_, bound)) => *bound = binding,
+            None => self.0.push((action, binding)),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Action, Binding)> + '_ {
+        self.0.iter().copied()
+    }
+
+    fn just_pressed(&self, action: Action, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self.get(action) {
+            Some(Binding::Key(key)) => keys.just_pressed(key),
+            Some(Binding::Mouse(button)) => mouse.just_pressed(button),
+            None => false,
+        }
+    }
+
+    fn pressed(&self, action: Action, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self.get(action) {
+            Some(Binding::Key(key)) => keys.pressed(key),
+            Some(Binding::Mouse(button)) => mouse.pressed(button),
+            None => false,
+        }
+    }
+
+    fn just_released(&self, action: Action, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self.get(action) {
+            Some(Binding::Key(key)) => keys.just_released(key),
+            Some(Binding::Mouse(button)) => mouse.just_released(button),
+            None => false,
+        }
+    }
+}
+
+type InputParams<'w> = (Res<'w, KeyBindings>, Res<'w, ButtonInput<KeyCode>>, Res<'w, ButtonInput<MouseButton>>);
+
+/// Run condition: the action's binding was pressed this frame.
+pub fn action_just_pressed(action: Action) -> impl FnMut(InputParams) -> bool {
+    move |(bindings, keys, mouse)| bindings.just_pressed(action, &keys, &mouse)
+}
+
+/// Run condition: the action's binding is held down.
+pub fn action_pressed(action: Action) -> impl FnMut(InputParams) -> bool {
+    move |(bindings, keys, mouse)| bindings.pressed(action, &keys, &mouse)
+}
+
+/// Run condition: the action's binding was released this frame.
+pub fn action_just_released(action: Action) -> impl FnMut(InputParams) -> bool {
+    move |(bindings, keys, mouse)| bindings.just_released(action, &keys, &mouse)
+}
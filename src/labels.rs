@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use crate::cursor::MainCamera;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::{Body, BodyId};
+
+/// Pixels between the top of a body and the bottom of its id label.
+const LABEL_GAP: f32 = 4.;
+
+const LABEL_FONT_SIZE: f32 = 14.;
+
+/// Whether every body shows its `BodyId` above it.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum IdLabelState {
+    #[default]
+    Hide,
+    Show,
+}
+
+/// UI text showing `body`'s id, kept over it in viewport space.
+#[derive(Component)]
+struct IdLabel {
+    body: Entity,
+}
+
+pub struct IdLabelPlugin;
+
+impl Plugin for IdLabelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<IdLabelState>()
+            .init_resource::<KeyBindings>()
+            .add_systems(Update, (
+                (sync_id_labels, place_id_labels).chain().run_if(in_state(IdLabelState::Show)),
+                toggle_id_labels.run_if(action_just_pressed(Action::ToggleIdLabels)),
+            ))
+            .add_systems(OnExit(IdLabelState::Show), despawn_id_labels);
+    }
+}
+
+/// Gives bodies spawned since the last frame a label, and drops the labels
+/// of bodies that are gone.
+fn sync_id_labels(
+    mut commands: Commands,
+    bodies: Query<(Entity, &BodyId), With<Body>>,
+    labels: Query<(Entity, &IdLabel)>,
+) {
+    let mut labeled = HashSet::new();
+    for (label, IdLabel { body }) in labels.iter() {
+        if bodies.contains(*body) {
+            labeled.insert(*body);
+        } else {
+            commands.entity(label).despawn_recursive();
+        }
+    }
+    for (body, id) in bodies.iter().filter(|(body, _)| !labeled.contains(body)) {
+        commands.spawn((
+            TextBundle::from_section(id.0.to_string(), TextStyle {
+                font_size: LABEL_FONT_SIZE,
+                color: Color::srgba(1., 1., 1., 0.7),
+                ..default()
+            }).with_style(Style {
+                position_type: PositionType::Absolute,
+                ..default()
+            }),
+            // Placed once its position is known.
+            Visibility::Hidden,
+            IdLabel { body },
+        ));
+    }
+}
+
+/// Moves labels whose body or the camera moved, and newly spawned ones.
+#[allow(clippy::type_complexity)]
+fn place_id_labels(
+    camera: Query<(&Camera, Ref<GlobalTransform>, Ref<Projection>), With<MainCamera>>,
+    bodies: Query<Ref<GlobalTransform>, With<Body>>,
+    mut labels: Query<(Ref<IdLabel>, &mut Style, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform, projection)) = camera.get_single() else {
+        return;
+    };
+    let view_changed = camera_transform.is_changed() || projection.is_changed();
+    for (label, mut style, mut visibility) in labels.iter_mut() {
+        let Ok(transform) = bodies.get(label.body) else {
+            continue;
+        };
+        if !(view_changed || label.is_added() || transform.is_changed()) {
+            continue;
+        }
+        let top = transform.translation() + Vec3::Y * transform.compute_transform().scale.y;
+        match camera.world_to_viewport(&camera_transform, top) {
+            Some(at) => {
+                style.left = Val::Px(at.x);
+                style.top = Val::Px(at.y - LABEL_GAP - LABEL_FONT_SIZE);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+fn despawn_id_labels(mut commands: Commands, labels: Query<Entity, With<IdLabel>>) {
+    for label in labels.iter() {
+        commands.entity(label).despawn_recursive();
+    }
+}
+
+fn toggle_id_labels(state: Res<State<IdLabelState>>, mut next_state: ResMut<NextState<IdLabelState>>) {
+    match state.get() {
+        IdLabelState::Show => next_state.set(IdLabelState::Hide),
+        IdLabelState::Hide => next_state.set(IdLabelState::Show),
+    }
+}
@@ -0,0 +1,197 @@
+use bevy::ecs::system::SystemState;
+use bevy::ecs::world::Command;
+use bevy::math::{DVec2, DVec3};
+use bevy::prelude::*;
+use crate::cursor::CursorCoords;
+use crate::input::{action_just_pressed, action_just_released, action_pressed, Action, KeyBindings};
+use crate::picking::body_at;
+use crate::simulation::{
+    force_law, make_room, Body, BodyAges, BodyConfig, BodyMesh, Config, Mass, Position, RenderOrigin,
+    SpawnBodyCommandExt, TestParticle,
+};
+
+/// Ticks of flight the predicted path covers: ten seconds at the default
+/// `physics_hz`.
+const PREVIEW_TICKS: usize = 640;
+
+/// Opacity of the ghost sphere.
+const GHOST_ALPHA: f32 = 0.25;
+
+/// The body being aimed under `Config::drag_launch`, shown as a translucent
+/// sphere. Released, it spawns as `Config::click_body_template` with this
+/// position and velocity.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct LaunchGhost {
+    /// Where the press was, and where the body will spawn.
+    pub position: DVec3,
+    pub velocity: DVec3,
+}
+
+/// Places a ghost at rest at `point`, replacing any earlier one.
+pub struct BeginLaunch(pub DVec2);
+
+impl Command for BeginLaunch {
+    fn apply(self, world: &mut World) {
+        discard_ghosts(world);
+        let position = self.0.extend(0.);
+        let radius = world.resource::<Config>().click_body_template.radius;
+        let origin = world.get_resource::<RenderOrigin>().copied().unwrap_or_default();
+        let entity = world.spawn((
+            LaunchGhost { position, velocity: DVec3::ZERO },
+            SpatialBundle::from_transform(Transform {
+                translation: origin.to_render(position),
+                scale: Vec3::splat(radius as f32),
+                ..default()
+            }),
+        )).id();
+        // Headless worlds have no meshes or materials; the ghost still aims.
+        let Some(mesh) = world.get_resource::<BodyMesh>().map(|meshes| meshes.sphere.clone()) else {
+            return;
+        };
+        if let Some(mut materials) = world.get_resource_mut::<Assets<StandardMaterial>>() {
+            let material = materials.add(StandardMaterial {
+                base_color: Color::srgba(1., 1., 1., GHOST_ALPHA),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            });
+            world.entity_mut(entity).insert((mesh, material));
+        }
+    }
+}
+
+/// Replaces the ghost with a real body. Like a click spawn, it first makes
+/// room at `Config::max_bodies`, and spawns nothing if it can't.
+pub struct CommitLaunch;
+
+impl Command for CommitLaunch {
+    fn apply(self, world: &mut World) {
+        let Some(ghost) = world.query::<&LaunchGhost>().iter(world).next().copied() else {
+            return;
+        };
+        discard_ghosts(world);
+        let mut state = SystemState::<(Commands, Res<Config>, BodyAges)>::new(world);
+        {
+            let (mut commands, config, ages) = state.get_mut(world);
+            if make_room(&mut commands, &config, &ages) {
+                commands.spawn_body(&BodyConfig {
+                    position: ghost.position,
+                    velocity: ghost.velocity,
+                    ..config.click_body_template.clone()
+                });
+            }
+        }
+        state.apply(world);
+    }
+}
+
+/// Drops the ghost without spawning anything.
+pub struct CancelLaunch;
+
+impl Command for CancelLaunch {
+    fn apply(self, world: &mut World) {
+        discard_ghosts(world);
+    }
+}
+
+fn discard_ghosts(world: &mut World) {
+    let ghosts: Vec<Entity> = world.query_filtered::<Entity, With<LaunchGhost>>().iter(world).collect();
+    for entity in ghosts {
+        world.despawn(entity);
+    }
+}
+
+/// Click-drag spawning, see `Config::drag_launch`: `BeginLaunch` on press,
+/// aiming while held, with the path the body would take against the other
+/// bodies held where they are, and `CommitLaunch` on release.
+/// `Action::CancelLaunch` drops the body instead.
+pub struct LaunchPlugin;
+
+impl Plugin for LaunchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyBindings>()
+            .init_resource::<RenderOrigin>()
+            .add_systems(Update, (
+                (
+                    begin_launch.run_if(action_just_pressed(Action::SpawnOrDrag)),
+                    aim_launch.run_if(action_pressed(Action::SpawnOrDrag)),
+                    commit_launch.run_if(action_just_released(Action::SpawnOrDrag)),
+                )
+                    .chain()
+                    .run_if(drag_launch_allowed),
+                cancel_launch.run_if(action_just_pressed(Action::CancelLaunch)),
+                draw_predicted_path,
+            ));
+    }
+}
+
+fn drag_launch_allowed(config: Res<Config>) -> bool {
+    config.allow_click_spawn && config.drag_launch.is_some()
+}
+
+fn begin_launch(
+    mut commands: Commands,
+    cursor: Res<CursorCoords>,
+    bodies: Query<(Entity, &Position, &BodyConfig), With<Body>>,
+) {
+    // Presses on an existing body are for picking it up, not spawning.
+    if body_at(cursor.0, bodies.iter()).is_some() {
+        return;
+    }
+    commands.add(BeginLaunch(cursor.0));
+}
+
+fn aim_launch(
+    cursor: Res<CursorCoords>,
+    config: Res<Config>,
+    origin: Res<RenderOrigin>,
+    mut ghosts: Query<(&mut LaunchGhost, &mut Transform)>,
+) {
+    let speed = config.drag_launch.unwrap_or(0.);
+    for (mut ghost, mut transform) in ghosts.iter_mut() {
+        // Pulled back like a slingshot, it flies away from the cursor.
+        ghost.velocity = ((ghost.position.truncate() - cursor.0) * speed).extend(0.);
+        transform.translation = origin.to_render(ghost.position);
+    }
+}
+
+fn commit_launch(mut commands: Commands) {
+    commands.add(CommitLaunch);
+}
+
+fn cancel_launch(mut commands: Commands) {
+    commands.add(CancelLaunch);
+}
+
+/// Integrates the ghost for `PREVIEW_TICKS` under the pull of the bodies
+/// as they are now, the way `update_body` would move it.
+#[allow(clippy::type_complexity)]
+fn draw_predicted_path(
+    mut gizmos: Gizmos,
+    config: Res<Config>,
+    origin: Res<RenderOrigin>,
+    ghosts: Query<&LaunchGhost>,
+    sources: Query<(&Position, &Mass, &BodyConfig), (With<Body>, Without<TestParticle>)>,
+) {
+    let Ok(ghost) = ghosts.get_single() else {
+        return;
+    };
+    let law = force_law(&config);
+    let (mut positions, mut masses, mut radii) = (Vec::new(), Vec::new(), Vec::new());
+    for (position, mass, body) in sources.iter() {
+        positions.push(position.0);
+        masses.push(mass.0);
+        radii.push(body.radius);
+    }
+    let template = &config.click_body_template;
+    let dt = config.timestep / config.physics_hz;
+    let (mut position, mut velocity) = (ghost.position, ghost.velocity);
+    let mut path = vec![position];
+    for _ in 0..PREVIEW_TICKS {
+        let acceleration = crate::nbody::acceleration_at(&law, position, template.radius, &positions, &masses, &radii);
+        config.integrator.step(&mut position, &mut velocity, acceleration, dt, config.max_speed);
+        path.push(position);
+    }
+    let color = template.trail_color.unwrap_or(LinearRgba::new(1., 1., 1., 0.4));
+    gizmos.linestrip(path.into_iter().map(|point| origin.to_render(point)), color);
+}
@@ -0,0 +1,25 @@
+pub mod simulation;
+pub mod nbody;
+pub mod trail;
+pub mod cursor;
+pub mod scenario;
+pub mod picking;
+pub mod orbit;
+pub mod spatial;
+pub mod encounters;
+pub mod overlay;
+pub mod input;
+pub mod selection;
+pub mod render;
+pub mod export;
+pub mod diagnostics;
+pub mod units;
+pub mod recording;
+pub mod snapshot;
+pub mod grid;
+pub mod density;
+pub mod labels;
+pub mod camera;
+pub mod cinematic;
+pub mod launch;
+pub mod profiling;
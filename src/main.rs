@@ -1,26 +1,37 @@
-use std::ops::{Add, Div, Sub};
 use bevy::core_pipeline::bloom::BloomSettings;
 use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::prelude::*;
-use bevy::math::DVec3;
-use crate::simulation::{BodyConfig, Config, GravityPlugin};
-
-mod simulation;
-mod cursor;
+use std::path::Path;
+use three_body::{camera, cinematic, cursor, density, diagnostics, export, grid, labels, launch, orbit, overlay, picking, profiling, recording, render, selection, snapshot};
+use three_body::scenario::{ScenarioLibrary, ScenarioPlugin};
+use three_body::simulation::{Config, GravityPlugin};
 
 fn main() {
-    let mut spawn_points = [
-        DVec3::new(0., 0., 0.),
-        DVec3::new(30., 0., 0.),
-        DVec3::new(0., 40., 0.),
-    ];
-    spawn_points = center_coordinates(spawn_points);
+    // The optional argument is a scenario file, or a directory of them.
+    let mut library = ScenarioLibrary::default();
+    let config = match std::env::args().nth(1) {
+        Some(path) if Path::new(&path).is_dir() => {
+            library = ScenarioLibrary::load_dir(&path).unwrap_or_else(|err| {
+                eprintln!("failed to read scenario directory {path}: {err}");
+                std::process::exit(1);
+            });
+            library.current()
+                .map(|(_, config)| config.clone())
+                .unwrap_or_else(Config::demo)
+        }
+        Some(path) => Config::load(&path).unwrap_or_else(|err| {
+            eprintln!("failed to load scenario {path}: {err}");
+            std::process::exit(1);
+        }),
+        None => Config::demo(),
+    };
     App::new()
         .add_plugins(
             DefaultPlugins
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: String::from("3 Body Problem"),
+                        present_mode: config.render.vsync.present_mode(),
                         ..default()
                     }),
                     ..default()
@@ -29,61 +40,34 @@ fn main() {
         )
         .insert_resource(ClearColor(Color::BLACK))
         .add_plugins(cursor::CursorPlugin)
-        .add_plugins(GravityPlugin::new(
-            Config {
-                initial_bodies: vec![
-                    BodyConfig {
-                        radius: 1.,
-                        mass: 1.,
-                        position: spawn_points[0],
-                        velocity: DVec3::new(0., 0., 0.),
-                        color: Some(LinearRgba::rgb(130.99, 50.32, 20.0)),
-                        trail_color: Some(
-                            LinearRgba::new(1.399, 0.532, 0.2, 0.4)
-                        ),
-                        trail_length: 300,
-                        ..default()
-                    },
-                    BodyConfig {
-                        radius: 1.,
-                        mass: 1.,
-                        position: spawn_points[1],
-                        velocity: DVec3::new(0., 0., 0.),
-                        color: Some(LinearRgba::rgb(20.0, 130.99, 50.32)),
-                        trail_color: Some(
-                            LinearRgba::new(0.2, 1.399, 0.532, 0.4)
-                        ),
-                        trail_length: 300,
-                        ..default()
-                    },
-                    BodyConfig {
-                        radius: 1.,
-                        mass: 1.,
-                        position: spawn_points[2],
-                        velocity: DVec3::new(0., 0., 0.),
-                        color: Some(LinearRgba::rgb(50.32, 20.0, 130.99)),
-                        trail_color: Some(
-                            LinearRgba::new(0.532, 0.2, 1.399, 0.4)
-                        ),
-                        trail_length: 300,
-                        ..default()
-                    },
-                ],
-                timestep: (3.1536e7 / 12.) * 2., // 2 months / second
-            },
-        ))
+        .add_plugins(picking::PickingPlugin)
+        .add_plugins(launch::LaunchPlugin)
+        .add_plugins(selection::SelectionPlugin)
+        .add_plugins(orbit::OrbitPlugin)
+        .add_plugins(grid::GridPlugin)
+        .add_plugins(density::DensityPlugin)
+        .add_plugins(labels::IdLabelPlugin)
+        .add_plugins(camera::CameraPlugin)
+        .add_plugins(cinematic::CinematicPlugin)
+        .add_plugins(diagnostics::DiagnosticsPlugin)
+        .add_plugins(overlay::OverlayPlugin)
+        .add_plugins(profiling::ProfilingPlugin)
+        .add_plugins(render::BackgroundPlugin)
+        .add_plugins(render::SpinPlugin)
+        .add_plugins(render::VectorPlugin)
+        .add_plugins(export::ExportPlugin)
+        .add_plugins(recording::RecordingPlugin)
+        .add_plugins(snapshot::SnapshotPlugin)
+        .add_plugins(GravityPlugin::new(config))
+        .insert_resource(library)
+        .add_plugins(ScenarioPlugin)
         .add_systems(Startup, setup)
         .run();
 }
 
-fn center_coordinates(triangle_verts: [DVec3; 3]) -> [DVec3; 3] {
-    let center = triangle_verts.iter()
-        .fold(DVec3::ZERO, |acc, v| acc + *v) / 3.0;
-    triangle_verts.map(|v| v - center)
-}
-
 fn setup(
     mut commands: Commands,
+    config: Res<Config>,
 ) {
     commands.spawn((
         Camera3dBundle {
@@ -92,24 +76,29 @@ fn setup(
                 ..default()
             },
             tonemapping: Tonemapping::TonyMcMapface,
-            transform: Transform::from_xyz(0.0, 0.0, 5.)
-                .looking_at(Vec3::default(), Vec3::Y),
-            projection: OrthographicProjection {
-                scale: 0.08,
-                ..default()
-            }.into(),
+            transform: config.render.initial_view.transform(),
+            projection: config.render.initial_view.projection.projection(),
             ..default()
         },
         BloomSettings::NATURAL,
         cursor::MainCamera,
     ));
 
-    commands.spawn(DirectionalLightBundle {
-        transform: Transform::from_xyz(50.0, 50.0, 50.0).looking_at(Vec3::ZERO, Vec3::Y),
-        directional_light: DirectionalLight {
-            illuminance: 1_500.,
+    let lighting = &config.render.lighting;
+    for (i, light) in lighting.lights.iter().enumerate() {
+        commands.spawn(DirectionalLightBundle {
+            transform: Transform::default().looking_to(light.direction, Vec3::Y),
+            directional_light: DirectionalLight {
+                illuminance: light.illuminance,
+                shadows_enabled: config.render.shadows && i == 0,
+                ..default()
+            },
             ..default()
-        },
+        });
+    }
+    commands.insert_resource(AmbientLight {
+        brightness: lighting.ambient_brightness,
         ..default()
     });
+    commands.insert_resource(config.render.msaa.msaa());
 }
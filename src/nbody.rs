@@ -0,0 +1,347 @@
+//! The N-body math on plain slices, free of Bevy: it depends only on
+//! `glam`, whose `DVec3` Bevy re-exports, and on `serde` for
+//! `Integrator`. It isn't `no_std`, since the float functions it calls
+//! come from `std`.
+
+use glam::DVec3;
+use serde::{Deserialize, Serialize};
+
+/// The pair force law `G m₁ m₂ / r^p`, optionally cut off beyond a
+/// distance and softened inside extended bodies.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForceLaw {
+    g: f64,
+    cutoff_sq: f64,
+    /// `(p + 1) / 2`: `delta / r^(p+1)` is the unit direction scaled by `1 / r^p`.
+    half_exponent: f64,
+    exponent: f64,
+    newtonian: bool,
+    extended_bodies: bool,
+}
+
+impl ForceLaw {
+    /// Newtonian gravity with constant `g`: point masses, inverse square,
+    /// no cutoff.
+    pub fn newtonian(g: f64) -> Self {
+        Self::new(g, 2., None, false)
+    }
+
+    /// `exponent` is `p`. Pairs further apart than `cutoff` don't interact.
+    /// With `extended_bodies`, a pair closer than the larger radius pulls
+    /// as if inside a uniform sphere, see `Config::extended_bodies`.
+    pub fn new(g: f64, exponent: f64, cutoff: Option<f64>, extended_bodies: bool) -> Self {
+        Self {
+            g,
+            cutoff_sq: cutoff.map_or(f64::INFINITY, |cutoff| cutoff * cutoff),
+            half_exponent: (exponent + 1.) / 2.,
+            exponent,
+            newtonian: exponent == 2.,
+            extended_bodies,
+        }
+    }
+
+    /// Factor `f` such that `delta * f * m` is the acceleration a body of
+    /// mass `m` at offset `delta` causes, or `None` if the pair doesn't
+    /// interact. `enclosing` is the larger of the two radii.
+    pub fn factor(&self, delta: DVec3, enclosing: f64) -> Option<f64> {
+        let distance_sq = delta.length_squared();
+        if distance_sq == 0.0 || distance_sq > self.cutoff_sq {
+            return None;
+        }
+        Some(if self.extended_bodies && distance_sq < enclosing * enclosing {
+            // Inside a uniform sphere the pull scales with the distance from
+            // its center, matching the point-mass law at the surface.
+            self.g / enclosing.powf(self.exponent + 1.)
+        } else if self.newtonian {
+            self.g / (distance_sq * distance_sq.sqrt())
+        } else {
+            self.g / distance_sq.powf(self.half_exponent)
+        })
+    }
+}
+
+/// Adds the mutual pull of every pair of bodies to `accelerations`,
+/// evaluating each pair once and applying it to both ends, so the forces
+/// within a pair are equal and opposite. `masses` are the masses bodies
+/// pull with: zero for a body that feels gravity but exerts none. Pairs
+/// where neither pulls are skipped. All slices have one entry per body.
+pub fn accumulate_pairwise(
+    law: &ForceLaw,
+    positions: &[DVec3],
+    masses: &[f64],
+    radii: &[f64],
+    accelerations: &mut [DVec3],
+) {
+    for i in 0..positions.len() {
+        for j in i + 1..positions.len() {
+            if masses[i] == 0. && masses[j] == 0. {
+                continue;
+            }
+            let delta = positions[j] - positions[i];
+            let Some(f) = law.factor(delta, radii[i].max(radii[j])) else {
+                continue;
+            };
+            let force_unit_mass = delta * f;
+            accelerations[i] += force_unit_mass * masses[j];
+            accelerations[j] -= force_unit_mass * masses[i];
+        }
+    }
+}
+
+/// `accumulate_pairwise` for pairs within `cutoff`, plus one mean-field term
+/// per body for everything further away. `near` lists candidate pairs
+/// `(i, j)`, `i < j`, each once; it must hold every pair within `cutoff`,
+/// and candidates beyond it count as far.
+///
+/// The far group's center of mass is found each call by subtraction: the
+/// total mass and first moment `Σ mᵢ rᵢ` of all bodies, minus those of the
+/// body and its near neighbours, divided out. A body then feels the far
+/// group as that mass at that point. Costs one pass plus the near pairs.
+/// A far group centered within `cutoff`, as for a body inside a ring of
+/// far bodies, is skipped: its pull largely cancels, and a point mass that
+/// close would be far off.
+pub fn accumulate_mean_field(
+    law: &ForceLaw,
+    cutoff: f64,
+    near: &[(usize, usize)],
+    positions: &[DVec3],
+    masses: &[f64],
+    radii: &[f64],
+    accelerations: &mut [DVec3],
+) {
+    let cutoff_sq = cutoff * cutoff;
+    let total_mass: f64 = masses.iter().sum();
+    let mass_scale: f64 = masses.iter().map(|m| m.abs()).sum();
+    // A body's own mass is never part of its far group.
+    let mut near_mass = masses.to_vec();
+    let mut near_moment: Vec<DVec3> = positions.iter().zip(masses).map(|(p, m)| *p * *m).collect();
+    let total_moment: DVec3 = near_moment.iter().sum();
+    for &(i, j) in near {
+        let delta = positions[j] - positions[i];
+        if delta.length_squared() > cutoff_sq {
+            continue;
+        }
+        near_mass[i] += masses[j];
+        near_moment[i] += positions[j] * masses[j];
+        near_mass[j] += masses[i];
+        near_moment[j] += positions[i] * masses[i];
+        if masses[i] == 0. && masses[j] == 0. {
+            continue;
+        }
+        let Some(f) = law.factor(delta, radii[i].max(radii[j])) else {
+            continue;
+        };
+        let force_unit_mass = delta * f;
+        accelerations[i] += force_unit_mass * masses[j];
+        accelerations[j] -= force_unit_mass * masses[i];
+    }
+    for i in 0..positions.len() {
+        let far_mass = total_mass - near_mass[i];
+        // What's left of the subtraction may be rounding alone.
+        if far_mass.abs() <= mass_scale * 1e-12 {
+            continue;
+        }
+        let delta = (total_moment - near_moment[i]) / far_mass - positions[i];
+        if delta.length_squared() <= cutoff_sq {
+            continue;
+        }
+        if let Some(f) = law.factor(delta, radii[i]) {
+            accelerations[i] += delta * f * far_mass;
+        }
+    }
+}
+
+/// Pull of the `sources` on a body at `position` with `radius`, summed in
+/// slice order. A source at exactly `position`, such as the body itself,
+/// adds nothing.
+pub fn acceleration_at(
+    law: &ForceLaw,
+    position: DVec3,
+    radius: f64,
+    positions: &[DVec3],
+    masses: &[f64],
+    radii: &[f64],
+) -> DVec3 {
+    positions.iter()
+        .zip(masses)
+        .zip(radii)
+        .filter_map(|((source, mass), source_radius)| {
+            let delta = *source - position;
+            let f = law.factor(delta, radius.max(*source_radius))?;
+            Some(delta * f * *mass)
+        })
+        .sum()
+}
+
+/// `Σ mᵢ vᵢ`.
+pub fn total_momentum(masses: &[f64], velocities: &[DVec3]) -> DVec3 {
+    masses.iter().zip(velocities).map(|(m, v)| *m * *v).sum()
+}
+
+/// How a body is advanced over one step.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Integrator {
+    /// Velocity first, then position from the new velocity. Symplectic, so
+    /// orbits stay bounded and energy oscillates instead of drifting.
+    #[default]
+    SemiImplicitEuler,
+    /// Textbook forward Euler: position from the old velocity. Orbits
+    /// spiral outwards as energy grows every step; meant for comparison.
+    Euler,
+}
+
+impl Integrator {
+    /// Advances one body by `dt` under `acceleration`. `max_speed` caps the
+    /// new velocity, see `Config::max_speed`.
+    pub fn step(self, position: &mut DVec3, velocity: &mut DVec3, acceleration: DVec3, dt: f64, max_speed: Option<f64>) {
+        let old_velocity = *velocity;
+        *velocity += acceleration * dt;
+        if let Some(max_speed) = max_speed {
+            *velocity = velocity.clamp_length_max(max_speed);
+        }
+        *position += match self {
+            Integrator::SemiImplicitEuler => *velocity,
+            Integrator::Euler => old_velocity,
+        } * dt;
+    }
+
+    /// `step` for every body.
+    pub fn step_all(self, positions: &mut [DVec3], velocities: &mut [DVec3], accelerations: &[DVec3], dt: f64) {
+        for ((position, velocity), acceleration) in positions.iter_mut().zip(velocities).zip(accelerations) {
+            self.step(position, velocity, *acceleration, dt, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three bodies of unequal mass, spread out in 3D and moving, so no
+    /// symmetry hides a sign error.
+    fn bodies() -> (Vec<DVec3>, Vec<DVec3>, Vec<f64>, Vec<f64>) {
+        let positions = vec![DVec3::new(0., 0., 0.), DVec3::new(3., 1., -0.5), DVec3::new(-2., 2.5, 1.)];
+        let velocities = vec![DVec3::new(0.1, -0.2, 0.), DVec3::new(-0.3, 0.4, 0.1), DVec3::new(0.2, 0., -0.2)];
+        (positions, velocities, vec![5., 1., 2.5], vec![0.1; 3])
+    }
+
+    fn accelerations(law: &ForceLaw, positions: &[DVec3], masses: &[f64], radii: &[f64]) -> Vec<DVec3> {
+        let mut accelerations = vec![DVec3::ZERO; positions.len()];
+        accumulate_pairwise(law, positions, masses, radii, &mut accelerations);
+        accelerations
+    }
+
+    #[test]
+    fn pairwise_forces_are_equal_and_opposite() {
+        let law = ForceLaw::newtonian(1.);
+        let positions = [DVec3::new(1., 2., 3.), DVec3::new(-2., 0.5, 1.)];
+        let masses = [3., 0.5];
+        let a = accelerations(&law, &positions, &masses, &[0.; 2]);
+        let (f1, f2) = (a[0] * masses[0], a[1] * masses[1]);
+        assert!(f1.length() > 0.);
+        assert!((f1 + f2).length() <= f1.length() * 1e-15);
+    }
+
+    #[test]
+    fn total_momentum_is_conserved() {
+        let law = ForceLaw::newtonian(1.);
+        let (mut positions, mut velocities, masses, radii) = bodies();
+        let initial = total_momentum(&masses, &velocities);
+        for _ in 0..1000 {
+            let a = accelerations(&law, &positions, &masses, &radii);
+            Integrator::SemiImplicitEuler.step_all(&mut positions, &mut velocities, &a, 1e-3);
+        }
+        assert!((total_momentum(&masses, &velocities) - initial).length() < 1e-12);
+    }
+
+    #[test]
+    fn force_follows_the_pair_axis_with_softening_and_cutoff() {
+        let law = ForceLaw::new(2., 2., Some(5.), true);
+        let delta = DVec3::new(1., -2., 2.);
+        let axis = delta.normalize();
+        // Outside both bodies: point-mass inverse square along the axis.
+        let pull = delta * law.factor(delta, 0.5).unwrap();
+        assert!((pull - axis * 2. / 9.).length() < 1e-15);
+        // Inside the larger radius, linear in the distance, still along the axis.
+        let inside = delta * law.factor(delta, 6.).unwrap();
+        assert!((inside - axis * 2. * 3. / 6_f64.powi(3)).length() < 1e-15);
+        // Beyond the cutoff, nothing.
+        assert_eq!(law.factor(delta * 2., 0.5), None);
+        assert_eq!(law.factor(DVec3::ZERO, 0.5), None);
+    }
+
+    #[test]
+    fn mean_field_matches_exact_sum_for_a_far_cluster() {
+        let law = ForceLaw::newtonian(1.);
+        // A probe far from a tight cluster of three.
+        let (cluster, _, masses, radii) = bodies();
+        let mut positions: Vec<DVec3> = cluster.iter().map(|p| *p * 0.01).collect();
+        positions.push(DVec3::new(500., 0., 0.));
+        let masses = [masses, vec![1.]].concat();
+        let radii = [radii, vec![0.1]].concat();
+        let exact = accelerations(&law, &positions, &masses, &radii);
+        let near = [(0, 1), (0, 2), (1, 2)];
+        let mut lumped = vec![DVec3::ZERO; positions.len()];
+        accumulate_mean_field(&law, 1., &near, &positions, &masses, &radii, &mut lumped);
+        assert!((lumped[3] - exact[3]).length() < exact[3].length() * 1e-6);
+    }
+
+    #[test]
+    fn negative_masses_push_each_other_apart() {
+        let law = ForceLaw::newtonian(1.);
+        let positions = [DVec3::new(-1., 0., 0.), DVec3::new(1., 0., 0.)];
+        let a = accelerations(&law, &positions, &[-1., -2.], &[0.; 2]);
+        assert!(a[0].x < 0. && a[1].x > 0.);
+        assert_eq!([a[0].y, a[0].z, a[1].y, a[1].z], [0.; 4]);
+    }
+
+    #[test]
+    fn exponent_two_is_inverse_square() {
+        let g = 11.334e-12;
+        let law = ForceLaw::new(g, 2., None, false);
+        // 3-4-5 triangle: r = 5, unit direction (0.6, 0.8, 0).
+        let positions = [DVec3::ZERO, DVec3::new(3., 4., 0.)];
+        let masses = [2e10, 7e10];
+        let a = accelerations(&law, &positions, &masses, &[0.; 2]);
+        let expected = DVec3::new(0.6, 0.8, 0.) * g * masses[1] / 25.;
+        assert!((a[0] - expected).length() <= expected.length() * 1e-15);
+        // The pre-`force_exponent` formula, `G m delta / r³`.
+        let general = DVec3::new(3., 4., 0.) * g / 25_f64.powf(1.5) * masses[1];
+        assert!((a[0] - general).length() <= expected.length() * 1e-15);
+    }
+
+    /// Bound on the relative error of the lumped far pull, for clusters
+    /// twenty times further apart than they are wide. The leading error is
+    /// the quadrupole term, around the square of that ratio.
+    const MEAN_FIELD_TOLERANCE: f64 = 1e-2;
+
+    #[test]
+    fn mean_field_error_is_bounded_for_distant_clusters() {
+        let law = ForceLaw::newtonian(1.);
+        let (cluster, _, cluster_masses, cluster_radii) = bodies();
+        // Two clusters about 5 wide, 100 apart.
+        let mut positions = cluster.clone();
+        positions.extend(cluster.iter().map(|p| DVec3::new(100., 10., 0.) - *p));
+        let masses = [cluster_masses.clone(), cluster_masses].concat();
+        let radii = [cluster_radii.clone(), cluster_radii].concat();
+        let exact = accelerations(&law, &positions, &masses, &radii);
+
+        let cutoff = 10.;
+        let near: Vec<(usize, usize)> = (0..6)
+            .flat_map(|i| (i + 1..6).map(move |j| (i, j)))
+            .filter(|(i, j)| positions[*i].distance(positions[*j]) <= cutoff)
+            .collect();
+        assert_eq!(near.len(), 6, "each cluster's three pairs are near");
+        let mut lumped = vec![DVec3::ZERO; positions.len()];
+        accumulate_mean_field(&law, cutoff, &near, &positions, &masses, &radii, &mut lumped);
+
+        // Measured against the far pull alone, which is all that's approximated.
+        for (i, (exact, lumped)) in exact.iter().zip(&lumped).enumerate() {
+            let own = if i < 3 { 0..3 } else { 3..6 };
+            let near_only = acceleration_at(&law, positions[i], radii[i], &positions[own.clone()], &masses[own.clone()], &radii[own]);
+            let far = *exact - near_only;
+            let error = (*exact - *lumped).length() / far.length();
+            assert!(error < MEAN_FIELD_TOLERANCE, "body {i}: relative error {error}");
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use std::f64::consts::TAU;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::selection::Selected;
+use crate::simulation::{Body, BodyConfig, Config, Mass, Position, RenderOrigin, TestParticle, Velocity};
+
+/// Eccentricities this close to 1 count as parabolic.
+pub const PARABOLIC_TOLERANCE: f64 = 1e-6;
+
+/// How many times harder than the runner-up a body must pull on the
+/// selection to count as the primary it orbits.
+const DOMINANCE_RATIO: f64 = 2.;
+
+/// Whether the analytic two-body orbit is drawn over the trails.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum KeplerOverlayState {
+    #[default]
+    Hide,
+    Show,
+}
+
+/// Osculating conic of a relative two-body state.
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitalElements {
+    /// Semi-latus rectum `h² / μ`; finite for every conic.
+    pub semi_latus_rectum: f64,
+    pub eccentricity: f64,
+    /// Specific orbital energy. Negative when bound.
+    pub energy: f64,
+    /// Unit vector towards periapsis.
+    pub periapsis: DVec3,
+    /// Unit vector 90° ahead of periapsis in the direction of motion.
+    pub normal_in_plane: DVec3,
+    /// Specific angular momentum `r × v`, normal to the orbital plane.
+    pub angular_momentum: DVec3,
+    /// Angle from periapsis to the current position, in radians.
+    pub true_anomaly: f64,
+}
+
+impl OrbitalElements {
+    /// Computes the orbit of a body at relative position `r` and velocity `v`
+    /// around a focus with gravitational parameter `mu`. Returns `None` for
+    /// degenerate (radial or coincident) states.
+    pub fn from_state(r: DVec3, v: DVec3, mu: f64) -> Option<Self> {
+        let h = r.cross(v);
+        if r.length_squared() == 0.0 || h.length_squared() == 0.0 || mu <= 0.0 {
+            return None;
+        }
+        let e_vec = v.cross(h) / mu - r.normalize();
+        let eccentricity = e_vec.length();
+        // A circular orbit has no periapsis; measure from the current position.
+        let periapsis = if eccentricity > 1e-9 { e_vec / eccentricity } else { r.normalize() };
+        let normal_in_plane = h.normalize().cross(periapsis);
+        Some(Self {
+            semi_latus_rectum: h.length_squared() / mu,
+            eccentricity,
+            energy: v.length_squared() / 2.0 - mu / r.length(),
+            periapsis,
+            normal_in_plane,
+            angular_momentum: h,
+            true_anomaly: r.dot(normal_in_plane).atan2(r.dot(periapsis)).rem_euclid(TAU),
+        })
+    }
+
+    pub fn is_parabolic(&self) -> bool {
+        (self.eccentricity - 1.0).abs() < PARABOLIC_TOLERANCE
+    }
+
+    /// Tilt of the orbital plane from the XY plane, in radians. Above π/2
+    /// the orbit runs clockwise seen from +Z.
+    pub fn inclination(&self) -> f64 {
+        (self.angular_momentum.z / self.angular_momentum.length()).clamp(-1.0, 1.0).acos()
+    }
+
+    /// Unit vector where the orbit rises through the XY plane. `None` for an
+    /// orbit in that plane, which has no nodes.
+    pub fn ascending_node(&self) -> Option<DVec3> {
+        DVec3::Z.cross(self.angular_momentum).try_normalize()
+            .filter(|_| self.inclination().sin() > 1e-9)
+    }
+
+    /// Angle from +X to the ascending node, in radians; `None` without one.
+    pub fn longitude_of_ascending_node(&self) -> Option<f64> {
+        self.ascending_node().map(|node| node.y.atan2(node.x).rem_euclid(TAU))
+    }
+
+    /// Angle from the ascending node to periapsis in the direction of
+    /// motion, in radians. For an orbit in the XY plane it is measured from
+    /// +X instead, i.e. the longitude of periapsis.
+    pub fn argument_of_periapsis(&self) -> f64 {
+        let reference = self.ascending_node().unwrap_or(DVec3::X);
+        let normal = self.angular_momentum.normalize();
+        reference.cross(self.periapsis).dot(normal)
+            .atan2(reference.dot(self.periapsis))
+            .rem_euclid(TAU)
+    }
+
+    pub fn is_bound(&self) -> bool {
+        self.energy < 0.0 && self.eccentricity < 1.0
+    }
+
+    /// Semi-major axis. Negative for hyperbolic orbits, infinite for parabolic.
+    pub fn semi_major_axis(&self) -> f64 {
+        self.semi_latus_rectum / (1.0 - self.eccentricity * self.eccentricity)
+    }
+
+    /// Position relative to the focus at true anomaly `theta`.
+    pub fn position_at(&self, theta: f64) -> DVec3 {
+        let radius = self.semi_latus_rectum / (1.0 + self.eccentricity * theta.cos());
+        (self.periapsis * theta.cos() + self.normal_in_plane * theta.sin()) * radius
+    }
+
+    /// Samples the conic. Bound orbits give a closed ellipse; unbound ones give
+    /// the branch out to `max_radius`.
+    pub fn sample(&self, segments: usize, max_radius: f64) -> Vec<DVec3> {
+        let (start, end) = if self.is_bound() {
+            (0.0, TAU)
+        } else {
+            // Limit the anomaly to where the orbit reaches `max_radius`.
+            let cos_limit = (self.semi_latus_rectum / max_radius - 1.0) / self.eccentricity;
+            let limit = cos_limit.clamp(-1.0, 1.0).acos();
+            (-limit, limit)
+        };
+        (0..=segments)
+            .map(|i| start + (end - start) * i as f64 / segments as f64)
+            .map(|theta| self.position_at(theta))
+            .collect()
+    }
+}
+
+/// The two-body orbit of the two most massive bodies, refreshed every frame.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub enum DominantPairOrbit {
+    /// Fewer than two bodies, or a degenerate relative state.
+    #[default]
+    None,
+    Unbound,
+    Bound {
+        semi_major_axis: f64,
+        /// Orbital period in simulated seconds.
+        period: f64,
+    },
+}
+
+impl DominantPairOrbit {
+    /// Classifies the orbit of a pair with relative state `r`, `v` and
+    /// gravitational parameter `mu = G * total_mass`.
+    pub fn from_state(r: DVec3, v: DVec3, mu: f64) -> Self {
+        match OrbitalElements::from_state(r, v, mu) {
+            None => DominantPairOrbit::None,
+            Some(orbit) if !orbit.is_bound() => DominantPairOrbit::Unbound,
+            Some(orbit) => {
+                let a = orbit.semi_major_axis();
+                DominantPairOrbit::Bound {
+                    semi_major_axis: a,
+                    period: TAU * (a.powi(3) / mu).sqrt(),
+                }
+            }
+        }
+    }
+}
+
+/// Osculating orbit of the `Selected` body about the body that pulls on
+/// it hardest, refreshed every frame.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub enum SelectedOrbit {
+    /// Nothing is selected.
+    #[default]
+    None,
+    /// No body pulls `DOMINANCE_RATIO` times harder than every other, so a
+    /// two-body orbit would say little about where the selection goes.
+    NoDominantBody,
+    /// The state relative to `primary` is radial.
+    Degenerate { primary: Entity },
+    Orbit {
+        primary: Entity,
+        elements: OrbitalElements,
+        /// `G * (m_primary + m_selected)`.
+        mu: f64,
+    },
+}
+
+pub struct OrbitPlugin;
+
+impl Plugin for OrbitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<KeplerOverlayState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<RenderOrigin>()
+            .init_resource::<DominantPairOrbit>()
+            .init_resource::<SelectedOrbit>()
+            .add_systems(Update, (
+                update_dominant_pair_orbit,
+                update_selected_orbit,
+                toggle_kepler_overlay.run_if(action_just_pressed(Action::ToggleKeplerOverlay)),
+                draw_kepler_overlay.run_if(in_state(KeplerOverlayState::Show)),
+            ));
+    }
+}
+
+fn update_dominant_pair_orbit(
+    mut orbit: ResMut<DominantPairOrbit>,
+    query: Query<(&Position, &Velocity, &Mass), With<Body>>,
+    config: Res<Config>,
+) {
+    let g = config.gravitational_constant();
+    let mut bodies: Vec<_> = query.iter().collect();
+    bodies.sort_by(|(_, _, a), (_, _, b)| b.0.total_cmp(&a.0));
+    *orbit = match bodies[..] {
+        [(p1, v1, m1), (p2, v2, m2), ..] => {
+            DominantPairOrbit::from_state(p2.0 - p1.0, v2.0 - v1.0, g * (m1.0 + m2.0))
+        }
+        _ => DominantPairOrbit::None,
+    };
+}
+
+#[allow(clippy::type_complexity)]
+fn update_selected_orbit(
+    mut orbit: ResMut<SelectedOrbit>,
+    config: Res<Config>,
+    selected: Query<(Entity, &Position, &Velocity, &Mass, Has<TestParticle>), (With<Body>, With<Selected>)>,
+    attractors: Query<(Entity, &Position, &Velocity, &Mass), (With<Body>, Without<TestParticle>)>,
+) {
+    let Ok((entity, position, velocity, mass, test_particle)) = selected.get_single() else {
+        *orbit = SelectedOrbit::None;
+        return;
+    };
+    let mut pulls: Vec<_> = attractors.iter()
+        .filter(|(other, _, _, m)| *other != entity && m.0 > 0.)
+        .filter_map(|(other, p, v, m)| {
+            let distance_sq = p.0.distance_squared(position.0);
+            (distance_sq > 0.).then(|| (m.0 / distance_sq, other, p.0, v.0, m.0))
+        })
+        .collect();
+    pulls.sort_by(|a, b| b.0.total_cmp(&a.0));
+    *orbit = match pulls[..] {
+        [] => SelectedOrbit::NoDominantBody,
+        [(strongest, ..), (runner_up, ..), ..] if strongest < runner_up * DOMINANCE_RATIO => {
+            SelectedOrbit::NoDominantBody
+        }
+        [(_, primary, p, v, m), ..] => {
+            let own_mass = if test_particle { 0. } else { mass.0.max(0.) };
+            let mu = config.gravitational_constant() * (m + own_mass);
+            match OrbitalElements::from_state(position.0 - p, velocity.0 - v, mu) {
+                Some(elements) => SelectedOrbit::Orbit { primary, elements, mu },
+                None => SelectedOrbit::Degenerate { primary },
+            }
+        }
+    };
+}
+
+fn draw_kepler_overlay(
+    mut gizmos: Gizmos,
+    query: Query<(&Position, &Velocity, &Mass, &BodyConfig), With<Body>>,
+    config: Res<Config>,
+    origin: Res<RenderOrigin>,
+) {
+    // Only meaningful for an isolated pair.
+    let mut bodies = query.iter();
+    let (Some((p1, v1, m1, c1)), Some((p2, v2, m2, c2)), None) =
+        (bodies.next(), bodies.next(), bodies.next()) else {
+        return;
+    };
+    let total_mass = m1.0 + m2.0;
+    let r = p2.0 - p1.0;
+    let Some(orbit) = OrbitalElements::from_state(r, v2.0 - v1.0, config.gravitational_constant() * total_mass) else {
+        return;
+    };
+    // Each body traces the relative orbit scaled about the barycenter.
+    let barycenter = (p1.0 * m1.0 + p2.0 * m2.0) / total_mass;
+    let relative = orbit.sample(256, r.length() * 10.0);
+    for (mass_fraction, config) in [(-m2.0 / total_mass, c1), (m1.0 / total_mass, c2)] {
+        let color = config.trail_color
+            .or(config.color)
+            .unwrap_or(LinearRgba::WHITE);
+        gizmos.linestrip(
+            relative.iter().map(|p| origin.to_render(barycenter + *p * mass_fraction)),
+            color,
+        );
+    }
+}
+
+fn toggle_kepler_overlay(
+    state: Res<State<KeplerOverlayState>>,
+    mut next_state: ResMut<NextState<KeplerOverlayState>>,
+) {
+    match state.get() {
+        KeplerOverlayState::Show => next_state.set(KeplerOverlayState::Hide),
+        KeplerOverlayState::Hide => next_state.set(KeplerOverlayState::Show),
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::BTreeMap;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::cursor::CursorCoords;
+use crate::diagnostics::{AngularMomentum, MinDistance};
+use crate::input::{action_just_pressed, Action, Binding, KeyBindings};
+use crate::orbit::{DominantPairOrbit, SelectedOrbit};
+use crate::picking::Hovered;
+use crate::profiling::{FrameTimings, ProfiledSystem, ProfilingState};
+use crate::scenario::ScenarioLibrary;
+use crate::selection::Selected;
+use crate::simulation::{Body, BodyConfig, BodyId, Config, Mass, Position, SimulationState, TimeDilation, Velocity};
+
+/// Whether the coordinate readout follows the cursor.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum CursorReadoutState {
+    #[default]
+    Hide,
+    Show,
+}
+
+/// Column of text readouts in the top-left corner.
+#[derive(Component)]
+struct InfoPanel;
+
+/// Name of the active scenario, hidden when no library is loaded.
+#[derive(Component)]
+struct ScenarioText;
+
+/// Body count per `BodyConfig::group`, hidden when no body has a group.
+#[derive(Component)]
+struct GroupCountsText;
+
+/// Id and mass of the selected body, hidden when nothing is selected.
+#[derive(Component)]
+struct SelectedBodyText;
+
+/// Osculating orbital elements of the selected body, hidden when nothing
+/// is selected.
+#[derive(Component)]
+struct OrbitalElementsText;
+
+/// Estimated period of the two most massive bodies.
+#[derive(Component)]
+struct OrbitalPeriodText;
+
+/// Magnitude of the total angular momentum about the center of mass.
+#[derive(Component)]
+struct AngularMomentumText;
+
+/// World coordinates under the cursor, plus the hovered body's state.
+/// Follows the cursor and hides while it is outside the window.
+#[derive(Component)]
+struct CursorReadout;
+
+/// Closest pair of bodies and their separation.
+#[derive(Component)]
+struct MinDistanceText;
+
+/// Per-system timings of the last frame, while profiling.
+#[derive(Component)]
+struct ProfilingText;
+
+/// Every keybind and what it does, toggled with `Action::ToggleHelp`.
+#[derive(Component)]
+struct HelpPanel;
+
+/// Dims the scene and shows "PAUSED" while the simulation is stopped.
+#[derive(Component)]
+struct PausedIndicator;
+
+/// Says how to add a body while there are none, centered in the window.
+#[derive(Component)]
+struct EmptyScenePrompt;
+
+pub struct OverlayPlugin;
+
+impl Plugin for OverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<CursorReadoutState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<TimeDilation>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, (
+                update_scenario_name.run_if(resource_changed::<ScenarioLibrary>),
+                update_group_counts,
+                update_selected_body,
+                update_orbital_elements.run_if(resource_changed::<SelectedOrbit>),
+                update_orbital_period
+                    .run_if(resource_changed::<DominantPairOrbit>.or_else(resource_changed::<TimeDilation>)),
+                update_angular_momentum.run_if(resource_changed::<AngularMomentum>),
+                update_min_distance.run_if(resource_changed::<MinDistance>),
+                update_help.run_if(resource_changed::<KeyBindings>),
+                update_paused_indicator.run_if(state_changed::<SimulationState>),
+                update_empty_scene_prompt,
+                toggle_help.run_if(action_just_pressed(Action::ToggleHelp)),
+                update_cursor_readout.run_if(in_state(CursorReadoutState::Show)),
+                toggle_cursor_readout.run_if(action_just_pressed(Action::ToggleCursorReadout)),
+            ))
+            .add_systems(OnExit(CursorReadoutState::Show), hide_cursor_readout)
+            .add_systems(Update, update_profiling.run_if(in_state(ProfilingState::Show)))
+            .add_systems(OnExit(ProfilingState::Show), hide_profiling);
+    }
+}
+
+fn overlay_text() -> TextBundle {
+    TextBundle::from_section("", TextStyle {
+        font_size: 16.,
+        color: Color::srgb(0.8, 0.8, 0.8),
+        ..default()
+    })
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.),
+                left: Val::Px(8.),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            ..default()
+        },
+        InfoPanel,
+    )).with_children(|panel| {
+        panel.spawn((overlay_text(), ScenarioText));
+        panel.spawn((overlay_text(), GroupCountsText));
+        panel.spawn((overlay_text(), SelectedBodyText));
+        panel.spawn((overlay_text(), OrbitalElementsText));
+        panel.spawn((overlay_text(), OrbitalPeriodText));
+        panel.spawn((overlay_text(), AngularMomentumText));
+        panel.spawn((overlay_text(), MinDistanceText));
+        let mut profiling = overlay_text();
+        profiling.visibility = Visibility::Hidden;
+        panel.spawn((profiling, ProfilingText));
+    });
+
+    let mut help = overlay_text();
+    help.style = Style {
+        position_type: PositionType::Absolute,
+        top: Val::Px(8.),
+        right: Val::Px(8.),
+        ..default()
+    };
+    help.visibility = Visibility::Hidden;
+    commands.spawn((help, HelpPanel));
+
+    let mut readout = overlay_text();
+    readout.style.position_type = PositionType::Absolute;
+    readout.visibility = Visibility::Hidden;
+    commands.spawn((readout, CursorReadout));
+
+    // Full-window and beneath the other panels, so only the scene dims.
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                padding: UiRect::top(Val::Px(8.)),
+                ..default()
+            },
+            background_color: Color::srgba(0., 0., 0., 0.25).into(),
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(-1),
+            ..default()
+        },
+        PausedIndicator,
+    )).with_children(|indicator| {
+        indicator.spawn(TextBundle::from_section("PAUSED", TextStyle {
+            font_size: 24.,
+            color: Color::srgb(0.8, 0.8, 0.8),
+            ..default()
+        }));
+    });
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        EmptyScenePrompt,
+    )).with_children(|prompt| {
+        prompt.spawn(TextBundle::from_section("", TextStyle {
+            font_size: 20.,
+            color: Color::srgb(0.8, 0.8, 0.8),
+            ..default()
+        }));
+    });
+}
+
+fn update_empty_scene_prompt(
+    config: Res<Config>,
+    bindings: Res<KeyBindings>,
+    bodies: Query<(), With<Body>>,
+    mut prompt: Query<(&mut Visibility, &Children), With<EmptyScenePrompt>>,
+    mut texts: Query<&mut Text>,
+) {
+    let Ok((mut visibility, children)) = prompt.get_single_mut() else {
+        return;
+    };
+    let empty = bodies.is_empty();
+    visibility.set_if_neq(if empty { Visibility::Inherited } else { Visibility::Hidden });
+    if !empty {
+        return;
+    }
+    let value = match bindings.get(Action::SpawnOrDrag).filter(|_| config.allow_click_spawn) {
+        Some(binding @ Binding::Mouse(_)) => format!("No bodies: click {} to add one", binding.label()),
+        Some(binding) => format!("No bodies: press {} to add one", binding.label()),
+        None => String::from("No bodies: list some in initial_bodies, or set allow_click_spawn"),
+    };
+    for &child in children {
+        if let Ok(mut text) = texts.get_mut(child) {
+            if text.sections[0].value != value {
+                text.sections[0].value = value.clone();
+            }
+        }
+    }
+}
+
+fn update_paused_indicator(
+    state: Res<State<SimulationState>>,
+    mut indicator: Query<&mut Visibility, With<PausedIndicator>>,
+) {
+    let Ok(mut visibility) = indicator.get_single_mut() else {
+        return;
+    };
+    *visibility = match state.get() {
+        SimulationState::Stopped => Visibility::Inherited,
+        SimulationState::Running => Visibility::Hidden,
+    };
+}
+
+fn update_selected_body(
+    selected: Query<(&BodyId, &Mass), With<Selected>>,
+    mut text: Query<(&mut Text, &mut Visibility), With<SelectedBodyText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+    match selected.get_single() {
+        Ok((id, mass)) => {
+            *visibility = Visibility::Inherited;
+            text.sections[0].value = format!("selected: body {}  mass {:.4}", id.0, mass.0);
+        }
+        Err(_) => *visibility = Visibility::Hidden,
+    }
+}
+
+fn update_orbital_elements(
+    orbit: Res<SelectedOrbit>,
+    ids: Query<&BodyId>,
+    mut text: Query<(&mut Text, &mut Visibility), With<OrbitalElementsText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+    *visibility = Visibility::Inherited;
+    let around = |primary: Entity| ids.get(primary).map_or(String::from("?"), |id| id.0.to_string());
+    text.sections[0].value = match *orbit {
+        SelectedOrbit::None => {
+            *visibility = Visibility::Hidden;
+            String::new()
+        }
+        SelectedOrbit::NoDominantBody => "orbit: no dominant body".to_string(),
+        SelectedOrbit::Degenerate { primary } => format!("orbit around body {}: radial", around(primary)),
+        SelectedOrbit::Orbit { primary, elements, mu } => {
+            let (kind, a) = if elements.is_parabolic() {
+                ("parabolic", "inf".to_string())
+            } else if elements.is_bound() {
+                ("elliptic", format!("{:.4}", elements.semi_major_axis()))
+            } else {
+                ("hyperbolic", format!("{:.4}", elements.semi_major_axis()))
+            };
+            let node = elements.longitude_of_ascending_node()
+                .map_or(String::from("-"), |node| format!("{:.1}", node.to_degrees()));
+            let mut value = format!(
+                "orbit around body {}: {kind}\na {a}  e {:.4}  inc {:.1} deg\nnode {node}  arg peri {:.1}  true anom {:.1} deg",
+                around(primary),
+                elements.eccentricity,
+                elements.inclination().to_degrees(),
+                elements.argument_of_periapsis().to_degrees(),
+                elements.true_anomaly.to_degrees(),
+            );
+            if elements.is_bound() {
+                let period = std::f64::consts::TAU * (elements.semi_major_axis().powi(3) / mu).sqrt();
+                value += &format!("\nperiod {period:.3e} s");
+            }
+            value
+        }
+    };
+}
+
+fn update_orbital_period(
+    orbit: Res<DominantPairOrbit>,
+    config: Res<Config>,
+    dilation: Res<TimeDilation>,
+    mut text: Query<(&mut Text, &mut Visibility), With<OrbitalPeriodText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+    *visibility = Visibility::Inherited;
+    text.sections[0].value = match *orbit {
+        DominantPairOrbit::None => {
+            *visibility = Visibility::Hidden;
+            String::new()
+        }
+        DominantPairOrbit::Unbound => "dominant pair: unbound".to_string(),
+        DominantPairOrbit::Bound { semi_major_axis, period } => format!(
+            "dominant pair: a = {semi_major_axis:.2}, T = {period:.3e} s ({:.1} s real time)",
+            dilation.real_seconds(&config, period),
+        ),
+    };
+}
+
+fn update_angular_momentum(
+    momentum: Res<AngularMomentum>,
+    mut query: Query<&mut Text, With<AngularMomentumText>>,
+) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("angular momentum: |L| = {:.6e}", momentum.0.length());
+    }
+}
+
+fn update_min_distance(
+    min_distance: Res<MinDistance>,
+    ids: Query<&BodyId>,
+    mut text: Query<(&mut Text, &mut Visibility), With<MinDistanceText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+    let Some(pair) = min_distance.0 else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+    let (Ok(a), Ok(b)) = (ids.get(pair.a), ids.get(pair.b)) else {
+        return;
+    };
+    text.sections[0].value = format!("closest pair: bodies {} and {}, {:.4}", a.0, b.0, pair.distance);
+}
+
+fn update_profiling(
+    timings: Res<FrameTimings>,
+    bodies: Query<(), With<Body>>,
+    mut text: Query<(&mut Text, &mut Visibility), With<ProfilingText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+    *visibility = Visibility::Inherited;
+    let mut value = format!("bodies: {}", bodies.iter().len());
+    for system in ProfiledSystem::ALL {
+        value += &format!("\n{}: {:.3} ms", system.name(), timings.get(system).as_secs_f64() * 1e3);
+    }
+    text.sections[0].value = value;
+}
+
+fn hide_profiling(mut text: Query<&mut Visibility, With<ProfilingText>>) {
+    for mut visibility in text.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn update_help(bindings: Res<KeyBindings>, mut query: Query<&mut Text, With<HelpPanel>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = bindings.iter()
+            .map(|(action, binding)| format!("{}: {}", binding.label(), action.description()))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_cursor_readout(
+    window: Query<&Window, With<PrimaryWindow>>,
+    cursor: Res<CursorCoords>,
+    hovered: Query<(&BodyId, &Position, &Velocity, &Mass), With<Hovered>>,
+    mut readout: Query<(&mut Text, &mut Style, &mut Visibility), With<CursorReadout>>,
+) {
+    let Ok((mut text, mut style, mut visibility)) = readout.get_single_mut() else {
+        return;
+    };
+    let Some(screen) = window.get_single().ok().and_then(|window| window.cursor_position()) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+    style.left = Val::Px(screen.x + 14.);
+    style.top = Val::Px(screen.y + 14.);
+    let mut value = format!("({:.2}, {:.2})", cursor.0.x, cursor.0.y);
+    if let Ok((id, position, velocity, mass)) = hovered.get_single() {
+        value += &format!(
+            "\nbody {}\npos ({:.3}, {:.3}, {:.3})\nvel ({:.3e}, {:.3e}, {:.3e})\nmass {:.4}",
+            id.0,
+            position.0.x, position.0.y, position.0.z,
+            velocity.0.x, velocity.0.y, velocity.0.z,
+            mass.0,
+        );
+    }
+    text.sections[0].value = value;
+}
+
+fn hide_cursor_readout(mut readout: Query<&mut Visibility, With<CursorReadout>>) {
+    for mut visibility in readout.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn toggle_cursor_readout(
+    state: Res<State<CursorReadoutState>>,
+    mut next_state: ResMut<NextState<CursorReadoutState>>,
+) {
+    match state.get() {
+        CursorReadoutState::Show => next_state.set(CursorReadoutState::Hide),
+        CursorReadoutState::Hide => next_state.set(CursorReadoutState::Show),
+    }
+}
+
+fn toggle_help(mut query: Query<&mut Visibility, With<HelpPanel>>) {
+    for mut visibility in query.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn update_scenario_name(
+    library: Res<ScenarioLibrary>,
+    mut text: Query<(&mut Text, &mut Visibility), With<ScenarioText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+    match library.current() {
+        Some((name, _)) => {
+            *visibility = Visibility::Inherited;
+            text.sections[0].value = format!(
+                "scenario: {name} ({}/{})",
+                library.current_index() + 1,
+                library.len(),
+            );
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+fn update_group_counts(
+    bodies: Query<&BodyConfig, With<Body>>,
+    mut text: Query<(&mut Text, &mut Visibility), With<GroupCountsText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+    let mut counts = BTreeMap::new();
+    for group in bodies.iter().filter_map(|body| body.group) {
+        *counts.entry(group).or_insert(0) += 1;
+    }
+    *visibility = if counts.is_empty() { Visibility::Hidden } else { Visibility::Inherited };
+    text.sections[0].value = counts.iter()
+        .map(|(group, count)| format!("group {group}: {count} bodies"))
+        .collect::<Vec<_>>()
+        .join("\n");
+}
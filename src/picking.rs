@@ -0,0 +1,119 @@
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use crate::cursor::CursorCoords;
+use crate::input::{action_just_pressed, action_just_released, action_pressed, Action, KeyBindings};
+use crate::simulation::{Body, BodyConfig, Position, RenderOrigin, SimulationState};
+use crate::trail::{Trail, TrailRef};
+
+/// The body currently held by the mouse, and where on it the cursor grabbed.
+#[derive(Resource, Default)]
+pub struct Dragging(Option<(Entity, DVec2)>);
+
+/// Marks the body under the cursor: what a click or drag would act on.
+#[derive(Component)]
+pub struct Hovered;
+
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Dragging>()
+            .init_resource::<RenderOrigin>()
+            .init_resource::<KeyBindings>()
+            .add_systems(
+                Update,
+                (
+                    start_drag.run_if(action_just_pressed(Action::SpawnOrDrag)),
+                    drag_body.run_if(action_pressed(Action::SpawnOrDrag)),
+                    end_drag.run_if(action_just_released(Action::SpawnOrDrag)),
+                )
+                    .chain()
+                    // Moving bodies while integrating would fight the integrator.
+                    .run_if(in_state(SimulationState::Stopped)),
+            )
+            .add_systems(Update, (update_hover, draw_hover_ring).chain())
+            .add_systems(OnEnter(SimulationState::Running), end_drag);
+    }
+}
+
+/// Returns the body whose disc (in the XY view plane) contains `point`,
+/// preferring the closest center when bodies overlap.
+pub(crate) fn body_at<'a>(
+    point: DVec2,
+    bodies: impl Iterator<Item = (Entity, &'a Position, &'a BodyConfig)>,
+) -> Option<Entity> {
+    bodies
+        .map(|(entity, position, config)| (entity, position.0.truncate().distance(point), config.radius))
+        .filter(|(_, distance, radius)| distance <= radius)
+        .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b))
+        .map(|(entity, _, _)| entity)
+}
+
+fn update_hover(
+    mut commands: Commands,
+    cursor: Res<CursorCoords>,
+    bodies: Query<(Entity, &Position, &BodyConfig), With<Body>>,
+    hovered: Query<Entity, With<Hovered>>,
+) {
+    let target = body_at(cursor.0, bodies.iter());
+    for entity in hovered.iter().filter(|entity| Some(*entity) != target) {
+        commands.entity(entity).remove::<Hovered>();
+    }
+    if let Some(entity) = target.filter(|entity| !hovered.contains(*entity)) {
+        commands.entity(entity).insert(Hovered);
+    }
+}
+
+fn draw_hover_ring(mut gizmos: Gizmos, query: Query<(&Transform, &BodyConfig), With<Hovered>>) {
+    for (transform, config) in query.iter() {
+        gizmos.circle(
+            transform.translation,
+            Dir3::Z,
+            config.radius as f32 * 1.3,
+            Color::srgba(1., 1., 1., 0.6),
+        );
+    }
+}
+
+fn start_drag(
+    mut dragging: ResMut<Dragging>,
+    cursor: Res<CursorCoords>,
+    bodies: Query<(Entity, &Position, &BodyConfig), With<Body>>,
+) {
+    dragging.0 = body_at(cursor.0, bodies.iter()).and_then(|entity| {
+        let (_, position, _) = bodies.get(entity).ok()?;
+        Some((entity, position.0.truncate() - cursor.0))
+    });
+}
+
+fn drag_body(
+    dragging: Res<Dragging>,
+    cursor: Res<CursorCoords>,
+    origin: Res<RenderOrigin>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut bodies: Query<(&mut Position, &mut Transform, Option<&TrailRef>), With<Body>>,
+    mut trails: Query<(&mut Trail, &Handle<Mesh>)>,
+) {
+    let Some((entity, offset)) = dragging.0 else {
+        return;
+    };
+    let Ok((mut position, mut transform, trail_ref)) = bodies.get_mut(entity) else {
+        return;
+    };
+    let target = cursor.0 + offset;
+    position.0.x = target.x;
+    position.0.y = target.y;
+    transform.translation = origin.to_render(position.0);
+
+    // Restart the trail at the new position so it doesn't draw a jump line.
+    if let Some(Ok((mut trail, mesh_handle))) = trail_ref.map(|t| trails.get_mut(t.0)) {
+        trail.restart(transform.translation);
+        if let Some(mesh) = meshes.get_mut(mesh_handle) {
+            trail.write_mesh(mesh);
+        }
+    }
+}
+
+fn end_drag(mut dragging: ResMut<Dragging>) {
+    dragging.0 = None;
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::utils::Instant;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::{gravity, update_body, PhysicsStep};
+use crate::trail::{draw_trail, update_trail};
+
+/// Whether the per-frame system timings are measured and shown.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum ProfilingState {
+    #[default]
+    Hide,
+    Show,
+}
+
+/// A system whose run time is measured while profiling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfiledSystem {
+    Gravity,
+    UpdateBody,
+    UpdateTrail,
+    DrawTrail,
+}
+
+impl ProfiledSystem {
+    pub const ALL: [ProfiledSystem; 4] = [
+        ProfiledSystem::Gravity,
+        ProfiledSystem::UpdateBody,
+        ProfiledSystem::UpdateTrail,
+        ProfiledSystem::DrawTrail,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ProfiledSystem::Gravity => "gravity",
+            ProfiledSystem::UpdateBody => "update_body",
+            ProfiledSystem::UpdateTrail => "update_trail",
+            ProfiledSystem::DrawTrail => "draw_trail",
+        }
+    }
+}
+
+/// Time spent in each `ProfiledSystem` during the last complete frame,
+/// summed over every fixed tick and substep in it.
+#[derive(Resource, Default)]
+pub struct FrameTimings {
+    last_frame: [Duration; 4],
+    current: [Duration; 4],
+    started: Option<Instant>,
+}
+
+impl FrameTimings {
+    pub fn get(&self, system: ProfiledSystem) -> Duration {
+        self.last_frame[system as usize]
+    }
+}
+
+/// Brackets the profiled systems with timer systems. Everything it adds
+/// only runs in `ProfilingState::Show`, so profiling costs one run
+/// condition per bracket when hidden.
+pub struct ProfilingPlugin;
+
+impl Plugin for ProfilingPlugin {
+    fn build(&self, app: &mut App) {
+        let profiling = in_state(ProfilingState::Show);
+        app.init_state::<ProfilingState>()
+            .init_resource::<FrameTimings>()
+            .init_resource::<KeyBindings>()
+            .add_systems(First, end_frame.run_if(profiling.clone()))
+            .add_systems(PhysicsStep, (
+                start_timer.before(gravity),
+                stop_timer(ProfiledSystem::Gravity).after(gravity).before(update_body),
+                start_timer.after(gravity).before(update_body),
+                stop_timer(ProfiledSystem::UpdateBody).after(update_body),
+            ).chain().run_if(profiling.clone()))
+            .add_systems(FixedUpdate, (
+                start_timer.before(update_trail),
+                stop_timer(ProfiledSystem::UpdateTrail).after(update_trail),
+            ).chain().run_if(profiling.clone()))
+            .add_systems(Update, (
+                (
+                    start_timer.before(draw_trail),
+                    stop_timer(ProfiledSystem::DrawTrail).after(draw_trail),
+                ).chain().run_if(profiling),
+                toggle_profiling.run_if(action_just_pressed(Action::ToggleProfiling)),
+            ));
+    }
+}
+
+fn start_timer(mut timings: ResMut<FrameTimings>) {
+    timings.started = Some(Instant::now());
+}
+
+fn stop_timer(system: ProfiledSystem) -> impl FnMut(ResMut<FrameTimings>) {
+    move |mut timings| {
+        if let Some(started) = timings.started.take() {
+            timings.current[system as usize] += started.elapsed();
+        }
+    }
+}
+
+fn end_frame(mut timings: ResMut<FrameTimings>) {
+    timings.last_frame = std::mem::take(&mut timings.current);
+}
+
+fn toggle_profiling(state: Res<State<ProfilingState>>, mut next_state: ResMut<NextState<ProfilingState>>) {
+    match state.get() {
+        ProfilingState::Show => next_state.set(ProfilingState::Hide),
+        ProfilingState::Hide => next_state.set(ProfilingState::Show),
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use bevy::ecs::world::Command;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::{
+    reset_with_bodies, run_physics_substeps, BodyColor, BodyConfig, BodyId, BodyIndex, Config, Mass,
+    Position, RenderOrigin, SimulationState,
+};
+
+/// File the record and replay keybinds write to and read from.
+const RECORDING_PATH: &str = "recording.3br";
+
+const MAGIC: &[u8; 4] = b"3BRC";
+
+/// Bumped whenever the layout below changes; older files are rejected.
+const FORMAT_VERSION: u32 = 1;
+
+/// Errors raised while reading or writing a recording file.
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(std::io::Error),
+    /// The file doesn't start with the recording magic bytes.
+    NotARecording,
+    /// Written by a different version of the format.
+    UnsupportedVersion(u32),
+    /// The file ends before the data its header announces.
+    Truncated,
+}
+
+impl Display for RecordingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordingError::Io(err) => write!(f, "io error: {err}"),
+            RecordingError::NotARecording => write!(f, "not a recording file"),
+            RecordingError::UnsupportedVersion(version) => write!(
+                f, "unsupported recording version {version}, expected {FORMAT_VERSION}",
+            ),
+            RecordingError::Truncated => write!(f, "recording file is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<std::io::Error> for RecordingError {
+    fn from(err: std::io::Error) -> Self {
+        RecordingError::Io(err)
+    }
+}
+
+/// Metadata needed to respawn a recorded body for playback.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecordedBody {
+    pub id: BodyId,
+    pub mass: f64,
+    pub radius: f64,
+    pub color: LinearRgba,
+}
+
+/// Positions of a fixed set of bodies, one frame per physics tick.
+///
+/// Bodies spawned after recording starts aren't captured; bodies removed
+/// during it hold their last position.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct Recording {
+    pub bodies: Vec<RecordedBody>,
+    /// `frames[i][j]` is the position of `bodies[j]` at tick `i`.
+    pub frames: Vec<Vec<DVec3>>,
+}
+
+impl Recording {
+    /// Writes the recording in a little-endian binary format: magic,
+    /// version, the body table, then every frame's positions.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordingError> {
+        let mut bytes = Vec::with_capacity(
+            16 + self.bodies.len() * 36 + self.frames.len() * self.bodies.len() * 24,
+        );
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.bodies.len() as u32).to_le_bytes());
+        for body in &self.bodies {
+            bytes.extend_from_slice(&body.id.0.to_le_bytes());
+            bytes.extend_from_slice(&body.mass.to_le_bytes());
+            bytes.extend_from_slice(&body.radius.to_le_bytes());
+            for channel in body.color.to_f32_array() {
+                bytes.extend_from_slice(&channel.to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&(self.frames.len() as u64).to_le_bytes());
+        for frame in &self.frames {
+            for position in frame {
+                for coordinate in position.to_array() {
+                    bytes.extend_from_slice(&coordinate.to_le_bytes());
+                }
+            }
+        }
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Reads a recording written by `save`, failing on foreign files or
+    /// other format versions instead of guessing.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let bytes = fs::read(path)?;
+        let mut reader = Reader(&bytes);
+        if reader.take(4)? != MAGIC {
+            return Err(RecordingError::NotARecording);
+        }
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(RecordingError::UnsupportedVersion(version));
+        }
+
+        let body_count = reader.u32()? as usize;
+        let mut bodies = Vec::with_capacity(body_count.min(reader.0.len()));
+        for _ in 0..body_count {
+            bodies.push(RecordedBody {
+                id: BodyId(reader.u32()?),
+                mass: reader.f64()?,
+                radius: reader.f64()?,
+                color: LinearRgba::new(reader.f32()?, reader.f32()?, reader.f32()?, reader.f32()?),
+            });
+        }
+        let frame_count = reader.u64()? as usize;
+        let mut frames = Vec::with_capacity(frame_count.min(reader.0.len()));
+        for _ in 0..frame_count {
+            let frame = (0..body_count)
+                .map(|_| Ok(DVec3::new(reader.f64()?, reader.f64()?, reader.f64()?)))
+                .collect::<Result<_, RecordingError>>()?;
+            frames.push(frame);
+        }
+        Ok(Self { bodies, frames })
+    }
+}
+
+/// Cursor over the bytes of a recording file.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], RecordingError> {
+        if self.0.len() < len {
+            return Err(RecordingError::Truncated);
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], RecordingError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, RecordingError> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, RecordingError> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Result<f32, RecordingError> {
+        self.array().map(f32::from_le_bytes)
+    }
+
+    fn f64(&mut self) -> Result<f64, RecordingError> {
+        self.array().map(f64::from_le_bytes)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum RecordingState {
+    #[default]
+    Idle,
+    /// Appending a frame to `Recording` every physics tick.
+    Recording,
+    /// Playing `Recording` back one frame per fixed tick, physics paused.
+    Replaying,
+}
+
+/// Frame of `Recording` shown next during playback.
+#[derive(Resource, Default)]
+struct ReplayFrame(usize);
+
+/// Id each of `Recording::bodies` was respawned under for playback, `None`
+/// where the spawn was refused.
+#[derive(Resource, Default)]
+struct ReplayBodies(Vec<Option<BodyId>>);
+
+/// Resets the simulation to `0.initial_bodies`, exactly as given, and
+/// fills `ReplayBodies` from the ids they get.
+struct StartReplay(Config);
+
+impl Command for StartReplay {
+    fn apply(self, world: &mut World) {
+        let bodies = self.0.initial_bodies.clone();
+        let ids = reset_with_bodies(world, self.0, bodies);
+        world.insert_resource(ReplayBodies(ids));
+    }
+}
+
+pub struct RecordingPlugin;
+
+impl Plugin for RecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<RecordingState>()
+            .init_resource::<Recording>()
+            .init_resource::<ReplayFrame>()
+            .init_resource::<ReplayBodies>()
+            .init_resource::<RenderOrigin>()
+            .init_resource::<KeyBindings>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    record_frame
+                        .after(run_physics_substeps)
+                        .run_if(in_state(RecordingState::Recording))
+                        .run_if(in_state(SimulationState::Running)),
+                    play_frame.run_if(in_state(RecordingState::Replaying)),
+                ),
+            )
+            .add_systems(Update, (
+                toggle_recording.run_if(action_just_pressed(Action::ToggleRecording)),
+                start_replay.run_if(action_just_pressed(Action::ReplayRecording)),
+            ));
+    }
+}
+
+fn toggle_recording(
+    state: Res<State<RecordingState>>,
+    mut next_state: ResMut<NextState<RecordingState>>,
+    mut recording: ResMut<Recording>,
+    index: Res<BodyIndex>,
+    bodies: Query<(&Mass, &BodyConfig, &BodyColor, &Position)>,
+) {
+    match state.get() {
+        RecordingState::Recording => {
+            next_state.set(RecordingState::Idle);
+            match recording.save(RECORDING_PATH) {
+                Ok(()) => info!("saved {} frames to {RECORDING_PATH}", recording.frames.len()),
+                Err(err) => error!("failed to save recording to {RECORDING_PATH}: {err}"),
+            }
+        }
+        RecordingState::Idle => {
+            let (bodies, first_frame) = index.iter()
+                .filter_map(|(id, entity)| {
+                    let (mass, config, color, position) = bodies.get(entity).ok()?;
+                    let body = RecordedBody { id, mass: mass.0, radius: config.radius, color: color.0 };
+                    Some((body, position.0))
+                })
+                .unzip();
+            *recording = Recording { bodies, frames: vec![first_frame] };
+            next_state.set(RecordingState::Recording);
+        }
+        RecordingState::Replaying => {}
+    }
+}
+
+fn record_frame(
+    mut recording: ResMut<Recording>,
+    index: Res<BodyIndex>,
+    positions: Query<&Position>,
+) {
+    let Recording { bodies, frames } = &mut *recording;
+    let frame = bodies.iter()
+        .enumerate()
+        .map(|(i, body)| {
+            index.get(body.id)
+                .and_then(|entity| positions.get(entity).ok())
+                .map_or_else(|| frames.last().map_or(DVec3::ZERO, |last| last[i]), |p| p.0)
+        })
+        .collect();
+    frames.push(frame);
+}
+
+fn start_replay(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut recording: ResMut<Recording>,
+    mut frame: ResMut<ReplayFrame>,
+    mut next_recording_state: ResMut<NextState<RecordingState>>,
+    mut next_simulation_state: ResMut<NextState<SimulationState>>,
+) {
+    let loaded = match Recording::load(RECORDING_PATH) {
+        Ok(loaded) if !loaded.frames.is_empty() => loaded,
+        Ok(_) => return warn!("{RECORDING_PATH} has no frames"),
+        Err(err) => return error!("failed to load recording from {RECORDING_PATH}: {err}"),
+    };
+    let mut replay_config = config.clone();
+    replay_config.initial_bodies = loaded.bodies.iter()
+        .zip(&loaded.frames[0])
+        .map(|(body, position)| BodyConfig {
+            radius: body.radius,
+            mass: body.mass,
+            position: *position,
+            color: Some(body.color),
+            ..default()
+        })
+        .collect();
+    commands.add(StartReplay(replay_config));
+    *recording = loaded;
+    frame.0 = 0;
+    next_simulation_state.set(SimulationState::Stopped);
+    next_recording_state.set(RecordingState::Replaying);
+}
+
+fn play_frame(
+    recording: Res<Recording>,
+    mut frame: ResMut<ReplayFrame>,
+    replay_bodies: Res<ReplayBodies>,
+    index: Res<BodyIndex>,
+    mut bodies: Query<(&mut Position, &mut Transform)>,
+    origin: Res<RenderOrigin>,
+    mut next_state: ResMut<NextState<RecordingState>>,
+) {
+    let Some(positions) = recording.frames.get(frame.0) else {
+        next_state.set(RecordingState::Idle);
+        return;
+    };
+    for (id, position) in replay_bodies.0.iter().zip(positions) {
+        let Some(entity) = id.and_then(|id| index.get(id)) else {
+            continue;
+        };
+        if let Ok((mut body_position, mut transform)) = bodies.get_mut(entity) {
+            body_position.0 = *position;
+            transform.translation = origin.to_render(*position);
+        }
+    }
+    frame.0 += 1;
+}
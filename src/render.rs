@@ -0,0 +1,398 @@
+use std::collections::BTreeMap;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::window::PresentMode;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use crate::camera::{CameraBookmark, InitialView};
+use crate::cinematic::CinematicConfig;
+use crate::density::DensityConfig;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::{Body, BodyConfig, Config, LastAcceleration, RenderOrigin, SimulationState, Velocity};
+
+/// Half the width of the square the starfield covers, in world units.
+const STARFIELD_EXTENT: f32 = 1000.;
+
+/// Depth of the starfield plane, far behind the bodies but inside the
+/// camera's far plane.
+const STARFIELD_DEPTH: f32 = -500.;
+
+/// Which per-body vector arrows `VectorPlugin` draws.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum VectorState {
+    #[default]
+    Hide,
+    Acceleration,
+    AccelerationAndVelocity,
+}
+
+/// Whether bodies with `BodyConfig::spin` rotate and show their spin axis.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum SpinState {
+    Off,
+    #[default]
+    On,
+}
+
+/// Presentation settings that don't affect the physics.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderConfig {
+    pub background: Background,
+    pub lighting: Lighting,
+    /// Seconds the camera takes to ease over to a new follow target.
+    pub camera_transition: f32,
+    /// Time constant, in seconds, of `CameraTarget::AutoFit` easing towards
+    /// the fitted view. Zooming back in uses four times this, so a body
+    /// briefly flung out doesn't make the view pump.
+    pub auto_fit_smoothing: f32,
+    /// Visible span as a multiple of the bodies' bounding box under
+    /// `CameraTarget::AutoFit`.
+    pub auto_fit_margin: f32,
+    /// Light bodies for real instead of making them glow: the key light
+    /// casts shadows, and body materials take their color (scaled into
+    /// 0..1) as a matte base color with no emission, so a body eclipsing
+    /// another darkens it. Changes the look a lot; off by default.
+    pub shadows: bool,
+    /// Length, in body radii, of a `VectorPlugin` arrow whose magnitude is
+    /// typical for the frame (the geometric mean over bodies). Length grows
+    /// with the log of the magnitude, so both tiny and huge vectors stay
+    /// readable next to each other.
+    pub vector_scale: f32,
+    /// Multisample antialiasing for body edges and line trails. Lower it on
+    /// slow GPUs; raise it for captures.
+    pub msaa: Antialiasing,
+    /// How frames are paced to the display. Only read when the window is
+    /// created, so it can't change at runtime or with the scenario.
+    pub vsync: Vsync,
+    /// Saved views by number key, see `crate::camera::CameraPlugin`.
+    pub camera_bookmarks: BTreeMap<u8, CameraBookmark>,
+    /// Camera position, facing and projection at startup.
+    pub initial_view: InitialView,
+    /// Slow motion on close approaches, toggled by
+    /// `crate::cinematic::CinematicPlugin`.
+    pub cinematic: CinematicConfig,
+    /// Grid and color of the `crate::density::DensityPlugin` heatmap.
+    pub density: DensityConfig,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            background: default(),
+            lighting: default(),
+            camera_transition: 0.6,
+            auto_fit_smoothing: 0.5,
+            auto_fit_margin: 1.2,
+            shadows: false,
+            vector_scale: 4.,
+            msaa: default(),
+            vsync: default(),
+            camera_bookmarks: BTreeMap::new(),
+            initial_view: default(),
+            cinematic: default(),
+            density: default(),
+        }
+    }
+}
+
+/// The scene's directional lights plus ambient fill.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Lighting {
+    /// One directional light each. The first is the key light and the only
+    /// one to cast shadows with `RenderConfig::shadows`. Defaults to that
+    /// key light alone, see `Lighting::three_point` for a fuller rig.
+    pub lights: Vec<LightConfig>,
+    /// `AmbientLight::brightness`, lighting the sides facing away.
+    pub ambient_brightness: f32,
+}
+
+impl Lighting {
+    /// Classic key / fill / rim setup: the default key light, a dimmer fill
+    /// from the opposite side softening its shadows, and a rim light from
+    /// behind the bodies picking out their edges against the background.
+    pub fn three_point() -> Self {
+        let key = LightConfig::default();
+        Self {
+            lights: vec![
+                key,
+                LightConfig { direction: Vec3::new(1., -0.5, -1.), illuminance: key.illuminance * 0.4 },
+                LightConfig { direction: Vec3::new(0., -0.5, 1.), illuminance: key.illuminance * 0.7 },
+            ],
+            ..default()
+        }
+    }
+}
+
+impl Default for Lighting {
+    fn default() -> Self {
+        Self {
+            lights: vec![LightConfig::default()],
+            ambient_brightness: AmbientLight::default().brightness,
+        }
+    }
+}
+
+/// A directional light, see `Lighting::lights`.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightConfig {
+    /// Direction the light shines in, world space.
+    pub direction: Vec3,
+    /// Illuminance in lux.
+    pub illuminance: f32,
+}
+
+impl Default for LightConfig {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::NEG_ONE,
+            illuminance: 1_500.,
+        }
+    }
+}
+
+/// MSAA sample count, see `RenderConfig::msaa`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Antialiasing {
+    Off,
+    Sample2,
+    /// Bevy's default.
+    #[default]
+    Sample4,
+    Sample8,
+}
+
+impl Antialiasing {
+    pub fn msaa(self) -> Msaa {
+        match self {
+            Antialiasing::Off => Msaa::Off,
+            Antialiasing::Sample2 => Msaa::Sample2,
+            Antialiasing::Sample4 => Msaa::Sample4,
+            Antialiasing::Sample8 => Msaa::Sample8,
+        }
+    }
+}
+
+/// Window present mode, see `RenderConfig::vsync`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Vsync {
+    /// Waits for the display's refresh, using `Relaxed` where the platform
+    /// has it and `On` otherwise. Bevy's default.
+    #[default]
+    Auto,
+    /// Waits for the display's refresh; no tearing. Supported everywhere.
+    On,
+    /// Like `On`, but shows a late frame right away instead of waiting for
+    /// the next refresh, tearing briefly rather than stuttering.
+    Relaxed,
+    /// Renders as fast as possible and shows the newest frame on each
+    /// refresh: low latency without tearing, at full GPU load. Not every
+    /// platform has it, and Bevy panics at startup where it's missing.
+    Mailbox,
+    /// Doesn't wait at all, tearing freely. Falls back to `Mailbox`, then
+    /// `On`, where that isn't supported.
+    Off,
+}
+
+impl Vsync {
+    pub fn present_mode(self) -> PresentMode {
+        match self {
+            Vsync::Auto => PresentMode::AutoVsync,
+            Vsync::On => PresentMode::Fifo,
+            Vsync::Relaxed => PresentMode::FifoRelaxed,
+            Vsync::Mailbox => PresentMode::Mailbox,
+            Vsync::Off => PresentMode::AutoNoVsync,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum Background {
+    #[default]
+    Black,
+    /// Random stars on a plane behind the scene.
+    Starfield {
+        /// Stars per 100 x 100 world units.
+        density: f32,
+        seed: u64,
+    },
+}
+
+/// Marks the starfield mesh. Its points are world positions, so
+/// `crate::simulation::rebase_origin` shifts it along with the bodies.
+#[derive(Component)]
+pub(crate) struct Starfield;
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RenderOrigin>()
+            .add_systems(Startup, spawn_background);
+    }
+}
+
+fn spawn_background(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<Config>,
+    origin: Res<RenderOrigin>,
+) {
+    let Background::Starfield { density, seed } = config.render.background else {
+        return;
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let area = (2. * STARFIELD_EXTENT / 100.).powi(2);
+    let count = (density.max(0.) * area) as usize;
+
+    let mut positions = Vec::with_capacity(count);
+    let mut colors = Vec::with_capacity(count);
+    for _ in 0..count {
+        positions.push([
+            rng.gen_range(-STARFIELD_EXTENT..STARFIELD_EXTENT),
+            rng.gen_range(-STARFIELD_EXTENT..STARFIELD_EXTENT),
+            STARFIELD_DEPTH,
+        ]);
+        // Kept dim so the stars barely bloom next to the bodies.
+        let brightness = rng.gen_range(0.05..0.6);
+        colors.push([brightness, brightness, brightness * rng.gen_range(0.8..1.2), 1.]);
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::PointList, RenderAssetUsages::RENDER_WORLD);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(mesh),
+            material: materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                ..default()
+            }),
+            transform: Transform::from_translation(origin.to_render(DVec3::ZERO)),
+            ..default()
+        },
+        Starfield,
+    ));
+}
+
+/// Spins bodies that have a `BodyConfig::spin` and draws their axis. Only
+/// touches `Transform::rotation`, so it can be left out without affecting
+/// the simulation.
+pub struct SpinPlugin;
+
+impl Plugin for SpinPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<SpinState>()
+            .init_resource::<KeyBindings>()
+            .add_systems(Update, (
+                spin_bodies
+                    .run_if(in_state(SpinState::On))
+                    .run_if(in_state(SimulationState::Running)),
+                draw_spin_axes.run_if(in_state(SpinState::On)),
+                toggle_spin.run_if(action_just_pressed(Action::ToggleSpin)),
+            ));
+    }
+}
+
+fn spin_bodies(time: Res<Time>, mut query: Query<(&BodyConfig, &mut Transform), With<Body>>) {
+    for (config, mut transform) in query.iter_mut() {
+        if let Some(spin) = config.spin {
+            transform.rotate(Quat::from_scaled_axis(spin * time.delta_seconds()));
+        }
+    }
+}
+
+fn draw_spin_axes(mut gizmos: Gizmos, query: Query<(&BodyConfig, &Transform), With<Body>>) {
+    for (config, transform) in query.iter() {
+        let Some(axis) = config.spin.and_then(|spin| spin.try_normalize()) else {
+            continue;
+        };
+        let reach = axis * config.radius as f32 * 1.5;
+        gizmos.line(
+            transform.translation - reach,
+            transform.translation + reach,
+            Color::srgba(1., 1., 1., 0.5),
+        );
+    }
+}
+
+fn toggle_spin(state: Res<State<SpinState>>, mut next_state: ResMut<NextState<SpinState>>) {
+    match state.get() {
+        SpinState::On => next_state.set(SpinState::Off),
+        SpinState::Off => next_state.set(SpinState::On),
+    }
+}
+
+/// Draws each body's acceleration, and optionally its velocity, as an arrow
+/// from its center. Cycled with `Action::CycleVectors`.
+pub struct VectorPlugin;
+
+impl Plugin for VectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<VectorState>()
+            .init_resource::<KeyBindings>()
+            .add_systems(Update, (
+                draw_vectors.run_if(not(in_state(VectorState::Hide))),
+                cycle_vectors.run_if(action_just_pressed(Action::CycleVectors)),
+            ));
+    }
+}
+
+/// Arrow lengths for `magnitudes`, in units of `scale`: one for the
+/// geometric mean of the nonzero magnitudes, growing with the log beyond.
+fn arrow_lengths(magnitudes: &[f64], scale: f32) -> Vec<f32> {
+    let logs: Vec<f64> = magnitudes.iter().filter(|m| **m > 0.).map(|m| m.ln()).collect();
+    if logs.is_empty() {
+        return vec![0.; magnitudes.len()];
+    }
+    let typical = (logs.iter().sum::<f64>() / logs.len() as f64).exp();
+    magnitudes.iter()
+        .map(|m| scale * (1. + m / typical).log2() as f32)
+        .collect()
+}
+
+fn draw_vectors(
+    mut gizmos: Gizmos,
+    state: Res<State<VectorState>>,
+    config: Res<Config>,
+    query: Query<(&Transform, &BodyConfig, &LastAcceleration, &Velocity), With<Body>>,
+) {
+    let bodies: Vec<_> = query.iter().collect();
+    let mut kinds = vec![(
+        bodies.iter().map(|(_, _, acceleration, _)| acceleration.0).collect::<Vec<_>>(),
+        Color::srgb(1., 0.45, 0.2),
+    )];
+    if *state.get() == VectorState::AccelerationAndVelocity {
+        kinds.push((
+            bodies.iter().map(|(_, _, _, velocity)| velocity.0).collect(),
+            Color::srgb(0.3, 0.8, 1.),
+        ));
+    }
+    for (vectors, color) in kinds {
+        let magnitudes: Vec<f64> = vectors.iter().map(|vector| vector.length()).collect();
+        let lengths = arrow_lengths(&magnitudes, config.render.vector_scale);
+        for (((transform, body, _, _), vector), length) in bodies.iter().zip(&vectors).zip(lengths) {
+            let Some(direction) = vector.try_normalize() else {
+                continue;
+            };
+            let start = transform.translation;
+            gizmos.arrow(start, start + direction.as_vec3() * length * body.radius as f32, color);
+        }
+    }
+}
+
+fn cycle_vectors(state: Res<State<VectorState>>, mut next_state: ResMut<NextState<VectorState>>) {
+    next_state.set(match state.get() {
+        VectorState::Hide => VectorState::Acceleration,
+        VectorState::Acceleration => VectorState::AccelerationAndVelocity,
+        VectorState::AccelerationAndVelocity => VectorState::Hide,
+    });
+}
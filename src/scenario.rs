@@ -0,0 +1,357 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+use std::f64::consts::TAU;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ron::ser::PrettyConfig;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::{BodyConfig, Config, ResetSimulation};
+
+/// Generated disk particles start no closer to the center than this
+/// fraction of the disk radius.
+const DISK_INNER_EDGE: f64 = 0.2;
+
+/// Total mass of a generated disk's particles, as a fraction of its
+/// central body.
+const DISK_MASS_FRACTION: f64 = 0.1;
+
+/// Errors raised while reading or writing a scenario file.
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    /// A malformed row in a plain-text table, by 1-based line number.
+    Table { line: usize, message: String },
+}
+
+impl Display for ScenarioError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "io error: {err}"),
+            ScenarioError::Parse(err) => write!(f, "parse error: {err}"),
+            ScenarioError::Serialize(err) => write!(f, "serialize error: {err}"),
+            ScenarioError::Table { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl From<std::io::Error> for ScenarioError {
+    fn from(err: std::io::Error) -> Self {
+        ScenarioError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ScenarioError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        ScenarioError::Parse(err)
+    }
+}
+
+impl From<ron::Error> for ScenarioError {
+    fn from(err: ron::Error) -> Self {
+        ScenarioError::Serialize(err)
+    }
+}
+
+impl Config {
+    /// Reads a RON scenario file, remembering where it came from so it can be
+    /// written back on exit.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let path = path.as_ref();
+        let mut config: Config = ron::from_str(&fs::read_to_string(path)?)?;
+        config.source_path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Reads bodies from a plain-text table with one `mass x y z vx vy vz`
+    /// row per body, separated by whitespace, as many astronomy datasets
+    /// ship. Blank lines and lines starting with `#` are skipped; everything
+    /// else keeps its default, including colors and trails.
+    pub fn from_ascii_table(reader: impl BufRead) -> Result<Self, ScenarioError> {
+        let mut initial_bodies = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let row = line.trim();
+            if row.is_empty() || row.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| ScenarioError::Table { line: index + 1, message };
+            let values = row.split_whitespace()
+                .map(|field| field.parse::<f64>().map_err(|err| error(format!("{field:?}: {err}"))))
+                .collect::<Result<Vec<_>, _>>()?;
+            let [mass, x, y, z, vx, vy, vz] = values[..] else {
+                return Err(error(format!(
+                    "expected 7 columns (mass x y z vx vy vz), found {}",
+                    values.len(),
+                )));
+            };
+            initial_bodies.push(BodyConfig {
+                mass,
+                position: DVec3::new(x, y, z),
+                velocity: DVec3::new(vx, vy, vz),
+                ..default()
+            });
+        }
+        Ok(Config { initial_bodies, ..default() })
+    }
+
+    /// The scene the demo opens with when given no scenario: three equal
+    /// bodies let go at rest from the corners of a triangle, at two months
+    /// per second.
+    pub fn demo() -> Self {
+        let mut spawn_points = [
+            DVec3::new(0., 0., 0.),
+            DVec3::new(30., 0., 0.),
+            DVec3::new(0., 40., 0.),
+        ];
+        spawn_points = center_coordinates(spawn_points);
+        Config {
+            initial_bodies: vec![
+                BodyConfig {
+                    radius: 1.,
+                    mass: 1.,
+                    position: spawn_points[0],
+                    velocity: DVec3::new(0., 0., 0.),
+                    color: Some(LinearRgba::rgb(130.99, 50.32, 20.0)),
+                    trail_color: Some(
+                        LinearRgba::new(1.399, 0.532, 0.2, 0.4)
+                    ),
+                    trail_length: 300,
+                    ..default()
+                },
+                BodyConfig {
+                    radius: 1.,
+                    mass: 1.,
+                    position: spawn_points[1],
+                    velocity: DVec3::new(0., 0., 0.),
+                    color: Some(LinearRgba::rgb(20.0, 130.99, 50.32)),
+                    trail_color: Some(
+                        LinearRgba::new(0.2, 1.399, 0.532, 0.4)
+                    ),
+                    trail_length: 300,
+                    ..default()
+                },
+                BodyConfig {
+                    radius: 1.,
+                    mass: 1.,
+                    position: spawn_points[2],
+                    velocity: DVec3::new(0., 0., 0.),
+                    color: Some(LinearRgba::rgb(50.32, 20.0, 130.99)),
+                    trail_color: Some(
+                        LinearRgba::new(0.532, 0.2, 1.399, 0.4)
+                    ),
+                    trail_length: 300,
+                    ..default()
+                },
+            ],
+            ..default()
+        }
+        .months_per_second(2.)
+    }
+
+    /// A galaxy-like demo: a central body of `central_mass` and `n` particles
+    /// spread evenly over a disk in the XY plane out to `radius`, each on
+    /// the circular orbit for the mass inside its radius so the disk is
+    /// rotationally supported. The particles together weigh a tenth of the
+    /// center and pull on each other; `extended_bodies` is on so their
+    /// close passes are softened instead of scattering particles out of the
+    /// plane. `timestep` is set so the rim turns once in about forty
+    /// seconds. The same `seed` gives the same disk.
+    pub fn rotating_disk(n: usize, radius: f64, central_mass: f64, seed: u64) -> Self {
+        let defaults = Config::default();
+        let g = defaults.gravitational_constant();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let particle_mass = central_mass * DISK_MASS_FRACTION / n.max(1) as f64;
+
+        // Uniform over the annulus area, innermost first so the mass inside
+        // each radius is a running sum.
+        let mut radii: Vec<f64> = (0..n)
+            .map(|_| radius * rng.gen_range(DISK_INNER_EDGE.powi(2)..=1.).sqrt())
+            .collect();
+        radii.sort_by(f64::total_cmp);
+
+        let mut initial_bodies = vec![BodyConfig {
+            radius: radius * 0.04,
+            mass: central_mass,
+            color: Some(LinearRgba::rgb(60., 40., 10.)),
+            trail_length: 0,
+            ..default()
+        }];
+        let mut enclosed = central_mass;
+        let mut inner = 0;
+        for &r in &radii {
+            // Particles at the same radius don't count each other.
+            while radii[inner] < r {
+                enclosed += particle_mass;
+                inner += 1;
+            }
+            let speed = (g * enclosed / r).sqrt();
+            let angle = rng.gen_range(0.0..TAU);
+            let direction = DVec3::new(angle.cos(), angle.sin(), 0.);
+            initial_bodies.push(BodyConfig {
+                radius: radius * 0.02,
+                mass: particle_mass,
+                position: direction * r,
+                // Counter-clockwise, perpendicular to the radius.
+                velocity: DVec3::new(-direction.y, direction.x, 0.) * speed,
+                trail_length: 30,
+                ..default()
+            });
+        }
+
+        let rim_period = TAU * (radius.powi(3) / (g * central_mass)).sqrt();
+        Config {
+            initial_bodies,
+            timestep: rim_period / 40.,
+            extended_bodies: true,
+            ..defaults
+        }
+    }
+
+    /// Two bodies of masses `m1` and `m2` on a Kepler orbit of semi-major
+    /// axis `a` and eccentricity `e`, about their common center of mass at
+    /// the origin. They start at periapsis, `m1` on -X and `m2` on +X,
+    /// moving counter-clockwise in the XY plane; they reach apoapsis half a
+    /// period later. `0 <= e < 1` gives an ellipse; a hyperbolic flyby
+    /// takes `e > 1` with a negative `a`, as
+    /// `OrbitalElements::semi_major_axis` reports it. Bound orbits get a
+    /// `timestep` of one period per ten seconds.
+    ///
+    /// Panics on a negative `e`, or where `a` and `1 - e` don't have the
+    /// same sign, which leaves no periapsis. That includes the parabolic
+    /// `e == 1`, whose semi-major axis is infinite; an `e` just off 1 comes
+    /// as close as needed.
+    pub fn two_body_orbit(m1: f64, m2: f64, a: f64, e: f64) -> Self {
+        let defaults = Config::default();
+        let mu = defaults.gravitational_constant() * (m1 + m2);
+        let periapsis = a * (1. - e);
+        assert!(
+            e >= 0. && periapsis > 0.,
+            "no Kepler orbit has a = {a} and e = {e}: an ellipse needs a > 0 and 0 <= e < 1, a hyperbola a < 0 and e > 1"
+        );
+        // Vis-viva at r = a (1 - e): v² = μ (2 / r - 1 / a) = μ (1 + e) / r.
+        let speed = (mu * (1. + e) / periapsis).sqrt();
+        let (share1, share2) = (m2 / (m1 + m2), m1 / (m1 + m2));
+        let body = |mass: f64, side: f64, share: f64| BodyConfig {
+            radius: periapsis * 0.05,
+            mass,
+            position: DVec3::X * side * share * periapsis,
+            velocity: DVec3::Y * side * share * speed,
+            trail_length: 300,
+            ..default()
+        };
+        let initial_bodies = vec![body(m1, -1., share1), body(m2, 1., share2)];
+
+        let timestep = if e < 1. {
+            TAU * (a.powi(3) / mu).sqrt() / 10.
+        } else {
+            defaults.timestep
+        };
+        Config {
+            initial_bodies,
+            timestep,
+            ..defaults
+        }
+    }
+
+    /// Writes this config as a RON scenario file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ScenarioError> {
+        let ron = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+        fs::write(path, ron)?;
+        Ok(())
+    }
+}
+
+fn center_coordinates(triangle_verts: [DVec3; 3]) -> [DVec3; 3] {
+    let center = triangle_verts.iter()
+        .fold(DVec3::ZERO, |acc, v| acc + *v) / 3.0;
+    triangle_verts.map(|v| v - center)
+}
+
+/// Scenarios loaded from a directory, switched between at runtime.
+#[derive(Resource, Default)]
+pub struct ScenarioLibrary {
+    scenarios: Vec<(String, Config)>,
+    current: usize,
+}
+
+impl ScenarioLibrary {
+    /// Loads every `.ron` file in `dir`, sorted by file name. Files that fail
+    /// to parse are skipped with a warning.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+            .collect();
+        paths.sort();
+
+        let scenarios = paths.into_iter()
+            .filter_map(|path| match Config::load(&path) {
+                Ok(config) => {
+                    let name = path.file_stem()?.to_string_lossy().into_owned();
+                    Some((name, config))
+                }
+                Err(err) => {
+                    warn!("skipping scenario {}: {err}", path.display());
+                    None
+                }
+            })
+            .collect();
+        Ok(Self { scenarios, current: 0 })
+    }
+
+    pub fn len(&self) -> usize {
+        self.scenarios.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenarios.is_empty()
+    }
+
+    /// Name and config of the active scenario.
+    pub fn current(&self) -> Option<(&str, &Config)> {
+        self.scenarios.get(self.current).map(|(name, config)| (name.as_str(), config))
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Moves `offset` scenarios forward (or back), wrapping around.
+    fn step(&mut self, offset: isize) -> Option<&Config> {
+        if self.scenarios.is_empty() {
+            return None;
+        }
+        let len = self.scenarios.len() as isize;
+        self.current = (self.current as isize + offset).rem_euclid(len) as usize;
+        self.current().map(|(_, config)| config)
+    }
+}
+
+pub struct ScenarioPlugin;
+
+impl Plugin for ScenarioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScenarioLibrary>()
+            .init_resource::<KeyBindings>()
+            .add_systems(Update, (
+                switch_scenario(1).run_if(action_just_pressed(Action::NextScenario)),
+                switch_scenario(-1).run_if(action_just_pressed(Action::PreviousScenario)),
+            ));
+    }
+}
+
+fn switch_scenario(offset: isize) -> impl FnMut(Commands, ResMut<ScenarioLibrary>) {
+    move |mut commands, mut library| {
+        if let Some(config) = library.step(offset) {
+            commands.add(ResetSimulation(config.clone()));
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use crate::cursor::CursorCoords;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::picking::body_at;
+use crate::simulation::{Body, BodyColor, BodyConfig, Config, Mass, Position, SimulationState, Velocity};
+
+/// Factor applied to the selected body's mass per key press.
+const MASS_STEP: f64 = 1.25;
+
+/// Mass magnitudes are kept above this so editing never makes a body
+/// massless or flips its sign.
+const MIN_MASS: f64 = 1e-6;
+
+/// Degrees the selected body's hue turns per key press.
+const HUE_STEP: f32 = 30.;
+
+/// Marks the body the user last clicked on.
+#[derive(Component)]
+pub struct Selected;
+
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyBindings>()
+            .add_systems(Update, (
+                select_on_click.run_if(action_just_pressed(Action::SpawnOrDrag)),
+                scale_selected_mass(MASS_STEP).run_if(action_just_pressed(Action::IncreaseMass)),
+                scale_selected_mass(1. / MASS_STEP).run_if(action_just_pressed(Action::DecreaseMass)),
+                cycle_selected_color.run_if(action_just_pressed(Action::CycleBodyColor)),
+                toggle_selected_trail_color.run_if(action_just_pressed(Action::ToggleTrailColor)),
+            ))
+            .add_systems(Update, (
+                nudge_selected_velocity(DVec3::NEG_X).run_if(action_just_pressed(Action::NudgeLeft)),
+                nudge_selected_velocity(DVec3::X).run_if(action_just_pressed(Action::NudgeRight)),
+                nudge_selected_velocity(DVec3::Y).run_if(action_just_pressed(Action::NudgeUp)),
+                nudge_selected_velocity(DVec3::NEG_Y).run_if(action_just_pressed(Action::NudgeDown)),
+                nudge_selected_velocity(DVec3::Z).run_if(action_just_pressed(Action::NudgeOut)),
+                nudge_selected_velocity(DVec3::NEG_Z).run_if(action_just_pressed(Action::NudgeIn)),
+            ).run_if(in_state(SimulationState::Stopped)));
+    }
+}
+
+/// Selects the body under the cursor, or clears the selection when clicking
+/// empty space.
+fn select_on_click(
+    mut commands: Commands,
+    cursor: Res<CursorCoords>,
+    bodies: Query<(Entity, &Position, &BodyConfig), With<Body>>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    let hit = body_at(cursor.0, bodies.iter());
+    for entity in selected.iter().filter(|entity| Some(*entity) != hit) {
+        commands.entity(entity).remove::<Selected>();
+    }
+    if let Some(entity) = hit {
+        commands.entity(entity).insert(Selected);
+    }
+}
+
+fn scale_selected_mass(factor: f64) -> impl FnMut(Query<&mut Mass, With<Selected>>) {
+    move |mut query| {
+        for mut mass in query.iter_mut() {
+            // Scale the magnitude so negative (repulsive) masses keep their sign.
+            mass.0 = (mass.0.abs() * factor).max(MIN_MASS).copysign(mass.0);
+        }
+    }
+}
+
+/// Adds `direction * Config::velocity_nudge` to the selected body's
+/// velocity. The orbital elements readout follows on the next frame, so
+/// an orbit can be tuned while paused before resuming.
+fn nudge_selected_velocity(direction: DVec3) -> impl FnMut(Res<Config>, Query<&mut Velocity, With<Selected>>) {
+    move |config, mut query| {
+        for mut velocity in query.iter_mut() {
+            velocity.0 += direction * config.velocity_nudge;
+        }
+    }
+}
+
+/// Turns the selected body's color around the hue wheel, keeping its
+/// brightness. `BodyConfig::color` is updated too, so the new color is
+/// saved with the scenario; the material follows the `BodyColor` change.
+fn cycle_selected_color(mut query: Query<(&mut BodyColor, &mut BodyConfig), With<Selected>>) {
+    for (mut color, mut config) in query.iter_mut() {
+        color.0 = rotate_hue(color.0, HUE_STEP);
+        config.color = Some(color.0);
+    }
+}
+
+/// Gives the selected body's trail the opposite hue, or lets it follow the
+/// body's color again if it already had its own.
+fn toggle_selected_trail_color(mut query: Query<(&BodyColor, &mut BodyConfig), With<Selected>>) {
+    for (color, mut config) in query.iter_mut() {
+        config.trail_color = match config.trail_color {
+            Some(_) => None,
+            None => Some(rotate_hue(color.0, 180.)),
+        };
+    }
+}
+
+/// `color` with its hue turned by `degrees`. Colors are HDR, so the hue is
+/// taken at unit peak and the peak restored after; greys pick up some
+/// saturation so they have a hue to turn.
+fn rotate_hue(color: LinearRgba, degrees: f32) -> LinearRgba {
+    let peak = color.red.max(color.green).max(color.blue);
+    if peak <= 0. {
+        return color;
+    }
+    let mut hsva = Hsva::from(LinearRgba::rgb(color.red / peak, color.green / peak, color.blue / peak));
+    hsva.saturation = hsva.saturation.max(0.8);
+    let turned = LinearRgba::from(hsva.rotate_hue(degrees));
+    let turned_peak = turned.red.max(turned.green).max(turned.blue);
+    LinearRgba::new(
+        turned.red * peak / turned_peak,
+        turned.green * peak / turned_peak,
+        turned.blue * peak / turned_peak,
+        color.alpha,
+    )
+}
@@ -1,21 +1,29 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 use bevy::ecs::world::Command;
-use bevy::input::common_conditions::input_just_pressed;
+use bevy::ecs::component::{ComponentHooks, StorageType};
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::color::Mix;
 use bevy::math::DVec3;
 use bevy::prelude::*;
-use bevy::render::mesh::PrimitiveTopology;
-use bevy::render::render_asset::RenderAssetUsages;
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use bevy::window::{PrimaryWindow, WindowFocused};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use crate::diagnostics::{CenterOfMass, RunStart, RunSummary};
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::nbody::ForceLaw;
+use crate::spatial::SpatialGrid;
+pub use crate::nbody::Integrator;
+use crate::trail::{Trail, TrailPlugin};
+use crate::units::UnitSystem;
 
-const G: f64 = 11.334e-12;
+/// Icosphere subdivisions used for the body mesh when none is configured.
+const DEFAULT_BODY_MESH_DETAIL: u8 = 3;
 
 // STATE
-#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
-pub enum TrailState {
-    Hide,
-    #[default]
-    Show,
-}
-
 #[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
 pub enum  SimulationState {
     #[default]
@@ -23,81 +31,675 @@ pub enum  SimulationState {
     Running,
 }
 
+// EVENTS
+/// Sent when the simulation goes from `Running` to `Stopped`, whatever
+/// caused it. Written during the state transition, so readers in `Update`
+/// see it the same frame with `State<SimulationState>` already `Stopped`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SimulationPaused;
+
+/// Sent when the simulation goes from `Stopped` to `Running`, with the same
+/// ordering as `SimulationPaused`. The initial `Stopped` state sends nothing.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SimulationResumed;
+
+/// Sent once per body that joins the simulation: initial and clicked bodies,
+/// and those respawned by `ResetSimulation` or a snapshot restore. Written
+/// when the spawn command is applied, so readers running after it (at the
+/// latest, the next frame) find `entity` with all its body components and
+/// `BodyId`. Refused spawns, e.g. of negative mass, send nothing.
+#[derive(Event, Clone)]
+pub struct BodySpawned {
+    pub entity: Entity,
+    pub config: BodyConfig,
+}
+
 // COMPONENTS
 #[derive(Bundle)]
 struct BodyBundle {
     body: Body,
+    id: BodyId,
     position: Position,
     velocity: Velocity,
     mass: Mass,
     acceleration: Acceleration,
-    mesh: PbrBundle,
+    last_acceleration: LastAcceleration,
+    color: BodyColor,
+    spatial: SpatialBundle,
     config: BodyConfig,
 }
 
+/// Marks every simulated body.
 #[derive(Component)]
-struct Body;
+pub struct Body;
 
-#[derive(Component)]
-struct Position(DVec3);
+/// The emissive color a body was spawned with, after palette fallbacks.
+#[derive(Component, Clone, Copy)]
+pub struct BodyColor(pub LinearRgba);
 
+/// Stable identifier assigned to each body in spawn order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BodyId(pub u32);
+
+impl Component for BodyId {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    // Keep `BodyIndex` in sync, and log, however the body goes away.
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(|mut world, entity, _| {
+            let id = *world.get::<BodyId>(entity).unwrap();
+            debug!(
+                id = id.0,
+                ?entity,
+                mass = world.get::<Mass>(entity).map(|mass| mass.0),
+                position = ?world.get::<Position>(entity).map(|position| position.0),
+                "body despawned",
+            );
+            if let Some(mut index) = world.get_resource_mut::<BodyIndex>() {
+                index.entities.remove(&id);
+            }
+        });
+    }
+}
+
+/// World-space position in f64. The body's `Transform` follows it,
+/// relative to `RenderOrigin`.
 #[derive(Component)]
-struct Velocity(DVec3);
+pub struct Position(pub DVec3);
 
+/// World-space velocity in f64.
 #[derive(Component)]
-struct Mass(f64);
+pub struct Velocity(pub DVec3);
 
 #[derive(Component)]
-struct Acceleration(DVec3);
+pub(crate) struct Mass(pub(crate) f64);
 
-#[derive(Bundle)]
-struct TrailBundle {
-    trail: Trail,
-    decay: TrailDecay,
-    color: TrailColor,
-    mesh: PbrBundle,
-}
+#[derive(Component)]
+pub(crate) struct Acceleration(DVec3);
 
+/// The acceleration the last integration step applied, kept because
+/// `update_body` clears `Acceleration` for the next step's forces.
 #[derive(Component, Default)]
-struct Trail {
-    max_length: usize,
-    points: Vec<Vec3>,
-}
+pub(crate) struct LastAcceleration(pub(crate) DVec3);
 
+/// Marks a body that feels gravity but exerts none, see `BodyConfig::test_particle`.
 #[derive(Component)]
-struct TrailDecay(Duration);
+pub(crate) struct TestParticle;
 
+/// Marks a body pinned in place, see `BodyConfig::anchored`.
 #[derive(Component)]
-struct TrailColor(Color);
+pub(crate) struct Anchored;
 
+/// Marks a body spawned from `Config::initial_bodies` rather than at runtime.
 #[derive(Component)]
-struct TrailRef(Entity);
+pub(crate) struct InitialBody;
+
+/// A population of bodies sharing the scene with the others but simulated
+/// apart from them: bodies only attract bodies of the same instance, so
+/// two copies of one setup can run side by side, e.g. with different
+/// integrators. Bodies without one make up a population of their own.
+/// Only `gravity` and the integrator tell instances apart; diagnostics,
+/// overlays and `Config::gw_inspiral` still see one system.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimInstance {
+    pub id: u32,
+    /// Overrides `Config::integrator` for this instance's bodies.
+    pub integrator: Option<Integrator>,
+}
 
 // RESOURCES
-#[derive(Clone, Resource)]
+#[derive(Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub initial_bodies: Vec<BodyConfig>,
+    /// `(magnitude, seed)`: nudge every initial body's position and
+    /// velocity by up to `magnitude` along X and Y, in scenario units, so
+    /// perfectly symmetric setups leave their unstable equilibrium right
+    /// away instead of waiting on rounding noise. The same seed gives the
+    /// same nudges; `None` or a zero magnitude spawns bodies exactly as
+    /// configured.
+    pub initial_jitter: Option<(f64, u64)>,
+    /// Spawn several copies of `initial_bodies`, each slightly perturbed and
+    /// in its own color, to watch nearly identical systems drift apart.
+    pub ensemble: Option<EnsembleConfig>,
+    /// Units of every position, velocity, mass and time in the scenario,
+    /// which also fix the gravitational constant.
+    pub units: UnitSystem,
+    /// Simulated seconds per real second. Each fixed tick advances
+    /// `timestep / physics_hz`, so this holds whatever `physics_hz` is.
     pub timestep: f64,
+    /// Rate of the `FixedUpdate` schedule that drives the integrator. Higher
+    /// rates give smaller steps (more accurate, smoother) at more CPU cost.
+    pub physics_hz: f64,
+    /// Most physics ticks run to catch up after a slow frame; sets
+    /// `Time<Virtual>`'s max delta to this many ticks. Lower values keep
+    /// the app responsive after a hitch, but the lost time is dropped, so
+    /// the simulation falls behind real time and the number of steps per
+    /// wall-clock second is no longer fixed. Defaults to 16, which at
+    /// 64 Hz is Bevy's own 250 ms.
+    pub max_catch_up_ticks: u32,
+    /// Number of equal integration steps each `FixedUpdate` tick is split into.
+    pub substeps: u32,
+    /// How each step moves bodies, unless their `SimInstance` says otherwise.
+    pub integrator: Integrator,
+    /// What to do at load when a single integration step is too long for
+    /// the closest initial pair, see `Config::shortest_dynamical_time`.
+    pub step_stability: StepStability,
+    /// Longest safe integration step as a fraction of the shortest
+    /// dynamical time. Defaults to 0.01.
+    pub stable_step_fraction: f64,
+    /// Record a trail point every this many physics ticks. Trails are
+    /// sampled in simulation time, so they look the same at any frame rate.
+    pub trail_interval: u32,
+    /// How trails are composited over what's behind them.
+    pub trail_blend: TrailBlend,
+    /// What happens to a trail once its body is gone, e.g. evicted by
+    /// `max_bodies` or merged by `gw_inspiral`.
+    pub orphaned_trails: OrphanedTrails,
+    /// Linear drag `a -= k * v` from a diffuse medium, applied by the
+    /// integrator on top of gravity. Non-conservative: with `k > 0` total
+    /// energy decays, so flybys can be captured. `Acceleration` stays pure
+    /// gravity. Defaults to 0.
+    pub drag_coefficient: f64,
+    /// Cap on every body's speed, applied by the integrator after each
+    /// step. A stability hack for sandbox play, not physics: it throws away
+    /// the kinetic energy of deep close passes, so energy and momentum are
+    /// no longer conserved. `None` leaves speeds alone.
+    pub max_speed: Option<f64>,
+    /// Experimental toy: drain orbital energy from the two most massive
+    /// bodies while they are bound, with the circular-orbit decay rate of
+    /// gravitational-wave emission, `da/dt = -(64/5) G³ m1 m2 (m1 + m2) /
+    /// (c⁵ a³)`, so the binary spirals in until the bodies touch and
+    /// merge. Not physics (there is no relativity here) and not
+    /// conservative; it runs as its own step between `gravity` and the
+    /// integrator.
+    pub gw_inspiral: bool,
+    /// The `c` of `gw_inspiral`, in scenario units. A slow toy value makes
+    /// the inspiral watchable: equal masses at circular speed `v` merge
+    /// after about `(c / v)⁵ / 80` orbits.
+    pub gw_light_speed: f64,
+    /// Exponent `p` of the force law `G m₁ m₂ / r^p`. Defaults to 2, Newtonian
+    /// gravity. Any other value is a toy model: orbits stop being closed
+    /// conics, energy is no longer the Newtonian potential's, and the
+    /// analytic Kepler overlay and period estimate no longer apply.
+    pub force_exponent: f64,
+    /// Skip pair interactions beyond this distance. Every pair is still
+    /// visited to measure how far apart it is, so only the force evaluation
+    /// of far pairs is saved, not the pass over all pairs. The missing
+    /// far-field pull means momentum and energy are no longer exactly
+    /// conserved. Leave `None` for exact gravity.
+    pub gravity_cutoff: Option<f64>,
+    /// Exact forces only for pairs within this distance; each body feels
+    /// the bodies beyond it as their combined mass at their center of mass,
+    /// see `crate::nbody::accumulate_mean_field`. Only the pairs a
+    /// `crate::spatial::SpatialGrid` finds within the cutoff are evaluated,
+    /// plus one term per body, so a few tight clusters far apart cost about
+    /// their pairs within each cluster, and keep most of the far pull that
+    /// `gravity_cutoff` drops. The far pull isn't returned in kind, so
+    /// momentum and energy drift. Takes precedence over `parallel_gravity`.
+    /// `None` for exact gravity.
+    pub mean_field_cutoff: Option<f64>,
+    /// Treat bodies as uniform spheres instead of point masses: once one
+    /// center is inside the larger body of a pair, their pull falls off
+    /// linearly to zero at the center (the shell theorem) instead of
+    /// diverging. Depends on `BodyConfig::radius`; both bodies feel the
+    /// same force, so momentum is still conserved. Off by default.
+    pub extended_bodies: bool,
+    /// Sum each body's acceleration independently with `par_iter_mut`
+    /// instead of walking pairs on one thread. Twice the force evaluations,
+    /// but split over every core and read from a flat snapshot rather than
+    /// fetched pair by pair, so it overtakes the pairwise loop from a few
+    /// dozen bodies even on a single core; below that, leave it off.
+    /// Reproducible, but not bit-identical to the pairwise loop.
+    pub parallel_gravity: bool,
+    /// NON-PHYSICAL, for sandbox experiments: scales each body's gravity
+    /// component-wise, e.g. `(1, 1, 0.2)` for a weak pull along Z that
+    /// settles into flattened structures. Pairs still pull equally and
+    /// oppositely, but no longer along the line between them, so energy
+    /// and angular momentum aren't conserved. `None` is isotropic.
+    pub gravity_anisotropy: Option<DVec3>,
+    /// Surface gap below which a `CloseApproach` event fires. `None` disables
+    /// the check.
+    pub close_approach_distance: Option<f64>,
+    /// Cell size of the spatial grid used for the close-approach check.
+    /// Defaults to the largest possible centre distance of a close pair;
+    /// sizes that aren't positive are ignored, and smaller ones are raised
+    /// to an eighth of that distance.
+    pub spatial_cell_size: Option<f64>,
+    /// Colors for bodies with a `group` but no explicit `color`, indexed by
+    /// group and wrapping around.
+    pub group_palette: Vec<LinearRgba>,
+    /// Colors for bodies with neither `color` nor `group`, assigned by
+    /// spawn order and wrapping around. An empty list walks the hue wheel
+    /// by the golden angle instead; `None` leaves such bodies grey.
+    pub auto_palette: Option<Vec<LinearRgba>>,
+    /// Pause when the primary window loses focus.
+    pub pause_on_focus_loss: bool,
+    /// Resume when focus returns, if the pause was automatic.
+    pub resume_on_focus: bool,
+    pub render: crate::render::RenderConfig,
+    /// Keep `RenderOrigin` on the center of mass, so a system far from the
+    /// world origin still renders at full f32 precision.
+    pub floating_origin: bool,
+    /// Number of icosphere subdivisions for the shared body mesh.
+    pub body_mesh_detail: u8,
+    /// Write the live body states back to a scenario file when the app exits.
+    pub save_on_exit: bool,
+    /// Spawn a body where empty space is clicked. Turn off for demos where
+    /// a stray click shouldn't change the scene; clicks still select and
+    /// drag bodies.
+    pub allow_click_spawn: bool,
+    /// What a click spawns. Its `position` is replaced by the cursor's.
+    pub click_body_template: BodyConfig,
+    /// Speed, per scenario unit dragged, of a body launched by click-drag.
+    /// With a value, pressing on empty space places a translucent ghost and
+    /// a predicted path, dragging pulls it back like a slingshot, and
+    /// releasing spawns it flying away from the cursor; see
+    /// `crate::launch::LaunchPlugin`. `None` spawns at rest on press.
+    pub drag_launch: Option<f64>,
+    /// Velocity added to the selected body per nudge key press while paused.
+    pub velocity_nudge: f64,
+    /// EXPERIMENTAL and non-physical: allow bodies with negative mass. Such a
+    /// body pushes every other body away and is itself accelerated towards
+    /// positive masses and away from negative ones. Off by default, when
+    /// negative-mass bodies are refused at spawn.
+    pub allow_negative_mass: bool,
+    /// What to do with a body spawned on top of another of its instance,
+    /// as repeated clicks on one spot or duplicated initial bodies do.
+    pub coincident_spawns: CoincidentSpawns,
+    /// Cap on the number of bodies. Spawning a body by clicking at the cap
+    /// first despawns the oldest runtime-spawned bodies and their trails
+    /// until it fits; the click is ignored if too few non-exempt bodies are
+    /// left. `None` is unbounded.
+    pub max_bodies: Option<usize>,
+    /// Let `max_bodies` evict bodies from `initial_bodies` too, not just
+    /// those spawned at runtime.
+    pub evict_initial_bodies: bool,
+    /// Where to write on exit. Falls back to the file the config was loaded from.
+    pub save_path: Option<PathBuf>,
+    /// The file this config was loaded from, if any.
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl Config {
+    /// `G` in the scenario's `units`.
+    pub fn gravitational_constant(&self) -> f64 {
+        self.units.gravitational_constant()
+    }
+
+    /// Longest frame `Time<Virtual>` passes on, see `max_catch_up_ticks`.
+    pub fn max_frame_delta(&self) -> Duration {
+        Duration::from_secs_f64(f64::from(self.max_catch_up_ticks.max(1)) / self.physics_hz)
+    }
+
+    /// `initial_bodies` as spawned: with `initial_jitter` applied, then
+    /// expanded into the `ensemble` copies.
+    pub fn spawned_initial_bodies(&self) -> Vec<BodyConfig> {
+        let mut bodies = self.initial_bodies.clone();
+        if let Some((magnitude, seed)) = self.initial_jitter.filter(|(magnitude, _)| *magnitude > 0.) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut nudge = || DVec3::new(
+                rng.gen_range(-magnitude..=magnitude),
+                rng.gen_range(-magnitude..=magnitude),
+                0.,
+            );
+            for body in &mut bodies {
+                body.position += nudge();
+                body.velocity += nudge();
+            }
+        }
+        match self.ensemble.filter(|ensemble| ensemble.count > 0) {
+            Some(ensemble) => ensemble.copies(&bodies),
+            None => bodies,
+        }
+    }
+
+    /// Simulated time covered by one integration step.
+    pub fn integration_step(&self) -> f64 {
+        self.timestep / self.physics_hz / f64::from(self.substeps.max(1))
+    }
+
+    /// Shortest `sqrt(r³ / (G * M))` over pairs of initial bodies: roughly
+    /// the time the closest pair takes to move appreciably. `None` with
+    /// fewer than two interacting bodies.
+    pub fn shortest_dynamical_time(&self) -> Option<f64> {
+        let g = self.gravitational_constant();
+        let bodies = &self.initial_bodies;
+        (0..bodies.len())
+            .flat_map(|i| (i + 1..bodies.len()).map(move |j| (&bodies[i], &bodies[j])))
+            .filter(|(a, b)| !(a.test_particle && b.test_particle))
+            .filter_map(|(a, b)| {
+                let r = a.position.distance(b.position);
+                let mu = g * (a.mass + b.mass);
+                (r > 0. && mu > 0.).then(|| (r.powi(3) / mu).sqrt())
+            })
+            .min_by(f64::total_cmp)
+    }
+
+    /// Applies `step_stability` to the initial bodies, logging what it found.
+    pub(crate) fn check_step_stability(&mut self) {
+        if self.step_stability == StepStability::Ignore {
+            return;
+        }
+        let Some(dynamical_time) = self.shortest_dynamical_time() else {
+            return;
+        };
+        let step = self.integration_step();
+        let safe_step = dynamical_time * self.stable_step_fraction;
+        if step <= safe_step {
+            return;
+        }
+        let safe_timestep = self.timestep * safe_step / step;
+        match self.step_stability {
+            StepStability::Warn => warn!(
+                "integration step {step:.3e} is {:.1}x the safe limit {safe_step:.3e} for the \
+                 closest pair (dynamical time {dynamical_time:.3e}); the orbit will likely blow up. \
+                 Lower `timestep` to {safe_timestep:.3e} or below, or raise `physics_hz` or `substeps`.",
+                step / safe_step,
+            ),
+            StepStability::Clamp => {
+                warn!(
+                    "clamping `timestep` from {:.3e} to {safe_timestep:.3e}: the integration step \
+                     exceeded the safe limit {safe_step:.3e} for the closest pair",
+                    self.timestep,
+                );
+                self.timestep = safe_timestep;
+            }
+            StepStability::Ignore => {}
+        }
+    }
+}
+
+/// Copies of the initial bodies run side by side, see `Config::ensemble`.
+/// Copy `k` of `count` is its own `SimInstance` with id `k`, colored by
+/// the golden-angle hue walk, and has every body moved off its configured
+/// position by `k / (count - 1) * perturbation` along a direction in the
+/// XY plane picked per body from `seed`. The first copy is unperturbed and
+/// no two copies start further than `perturbation` apart.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EnsembleConfig {
+    pub count: u32,
+    pub perturbation: f64,
+    pub seed: u64,
+}
+
+impl EnsembleConfig {
+    fn copies(&self, bodies: &[BodyConfig]) -> Vec<BodyConfig> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let directions: Vec<DVec3> = bodies.iter()
+            .map(|_| {
+                let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+                DVec3::new(angle.cos(), angle.sin(), 0.)
+            })
+            .collect();
+        let steps = f64::from(self.count.saturating_sub(1).max(1));
+        (0..self.count)
+            .flat_map(|k| {
+                let offset = self.perturbation * f64::from(k) / steps;
+                let color = auto_color(&[], k as usize);
+                bodies.iter().zip(&directions).map(move |(body, direction)| BodyConfig {
+                    position: body.position + *direction * offset,
+                    color: Some(color),
+                    trail_color: None,
+                    instance: Some(SimInstance {
+                        id: k,
+                        integrator: body.instance.and_then(|instance| instance.integrator),
+                    }),
+                    ..body.clone()
+                })
+            })
+            .collect()
+    }
+}
+
+/// Handling of a body spawned at or near another body's position, see
+/// `Config::coincident_spawns`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CoincidentSpawns {
+    /// Spawn it anyway. A pair at exactly one point pulls neither way and
+    /// overlaps on screen until something else separates them.
+    #[default]
+    Allow,
+    /// Move it clear of every body closer than this distance: radially
+    /// away from the closest, or along +X from one exactly beneath it.
+    Nudge(f64),
+    /// Refuse it if it lands exactly on another body.
+    Reject,
+}
+
+/// Response to an initial integration step that is likely unstable.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StepStability {
+    Ignore,
+    /// Log a warning suggesting a safe `timestep`.
+    #[default]
+    Warn,
+    /// Lower `Config::timestep` to the safe limit.
+    Clamp,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             initial_bodies: vec![],
+            initial_jitter: None,
+            ensemble: None,
+            units: UnitSystem::default(),
             timestep: 1.,
+            physics_hz: 64.,
+            max_catch_up_ticks: 16,
+            substeps: 1,
+            integrator: default(),
+            step_stability: StepStability::Warn,
+            stable_step_fraction: 0.01,
+            trail_interval: 1,
+            trail_blend: TrailBlend::Opaque,
+            orphaned_trails: OrphanedTrails::Despawn,
+            drag_coefficient: 0.,
+            max_speed: None,
+            gw_inspiral: false,
+            gw_light_speed: 1.,
+            force_exponent: 2.,
+            gravity_cutoff: None,
+            mean_field_cutoff: None,
+            extended_bodies: false,
+            parallel_gravity: false,
+            gravity_anisotropy: None,
+            close_approach_distance: None,
+            spatial_cell_size: None,
+            group_palette: vec![
+                LinearRgba::rgb(40., 12., 4.),
+                LinearRgba::rgb(4., 16., 40.),
+                LinearRgba::rgb(8., 40., 10.),
+                LinearRgba::rgb(36., 6., 36.),
+                LinearRgba::rgb(40., 34., 4.),
+                LinearRgba::rgb(4., 36., 36.),
+            ],
+            auto_palette: None,
+            pause_on_focus_loss: true,
+            resume_on_focus: true,
+            render: default(),
+            floating_origin: true,
+            body_mesh_detail: DEFAULT_BODY_MESH_DETAIL,
+            save_on_exit: false,
+            allow_click_spawn: true,
+            click_body_template: BodyConfig {
+                radius: 0.2,
+                mass: 0.2,
+                color: Some(LinearRgba::rgb(5., 5., 5.)),
+                trail_color: Some(LinearRgba::new(1., 1., 1., 0.4)),
+                trail_length: 20,
+                ..default()
+            },
+            drag_launch: None,
+            velocity_nudge: 0.05,
+            allow_negative_mass: false,
+            coincident_spawns: CoincidentSpawns::Allow,
+            max_bodies: None,
+            evict_initial_bodies: false,
+            save_path: None,
+            source_path: None,
         }
     }
 }
 
-#[derive(Clone, Component)]
+#[derive(Clone, Component, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BodyConfig {
     pub radius: f64,
     pub mass: f64,
     pub position: DVec3,
     pub velocity: DVec3,
     pub color: Option<LinearRgba>,
+    /// Glow intensity, separate from hue: the body's color (explicit or
+    /// from a palette) is scaled to a peak channel of 1 and multiplied by
+    /// this. Around 1 barely glows, the demo bodies sit near 130. `None`
+    /// uses the color's raw HDR values as given.
+    pub brightness: Option<f32>,
     pub trail_color: Option<LinearRgba>,
     pub trail_length: usize,
+    /// Minimum distance between stored trail points. `trail_length` still caps
+    /// the number of points, so the trail covers up to roughly
+    /// `trail_length * trail_min_spacing` of path.
+    pub trail_min_spacing: Option<f32>,
+    /// When the trail takes a new point.
+    pub trail_spacing_mode: TrailSpacing,
+    pub trail_coloring: TrailColoring,
+    /// Draw the trail as a flat ribbon this wide, in world units, instead
+    /// of a thin line. Ribbons lie in the XY view plane.
+    pub trail_width: Option<f32>,
+    /// How a ribbon trail turns corners.
+    pub trail_join: TrailJoin,
+    pub shape: BodyShape,
+    /// A massless probe: it is pulled by other bodies but never pulls on
+    /// them, whatever its `mass`. A body with zero mass behaves the same way.
+    pub test_particle: bool,
+    /// Cluster the body belongs to. Uncolored bodies take their group's
+    /// color from `Config::group_palette`.
+    pub group: Option<u32>,
+    /// Pin the body in place: it still pulls on others, but the integrator
+    /// never moves it, as if it had infinite inertia.
+    pub anchored: bool,
+    /// Population the body is simulated in, see `SimInstance`.
+    pub instance: Option<SimInstance>,
+    /// Cosmetic angular velocity in radians per real second, about a
+    /// world-space axis. Only rotates the rendered mesh, see
+    /// `crate::render::SpinPlugin`; the physics treats bodies as points.
+    pub spin: Option<Vec3>,
+}
+
+/// When a body's trail takes a new point.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TrailSpacing {
+    /// Every `Config::trail_interval` physics ticks, subject to
+    /// `BodyConfig::trail_min_spacing`. Fast stretches get sparse points
+    /// and slow ones bunch up.
+    #[default]
+    Time,
+    /// Every this many world units travelled, checked every physics tick
+    /// and filled in along the straight line from the last point when the
+    /// body moved further than that in one tick, so points stay evenly
+    /// spaced whatever the speed. Ignores `trail_interval` and
+    /// `trail_min_spacing`.
+    ArcLength(f32),
+}
+
+/// How a body's trail is colored.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub enum TrailColoring {
+    /// A single color from `BodyConfig::trail_color`.
+    #[default]
+    Flat,
+    /// Each point is colored by the body's speed when it was recorded,
+    /// blending from `slow` at rest to `fast` at `max_speed` and above.
+    Speed {
+        slow: LinearRgba,
+        fast: LinearRgba,
+        max_speed: f64,
+    },
+}
+
+impl TrailColoring {
+    pub(crate) fn color_at_speed(&self, speed: f64) -> LinearRgba {
+        match *self {
+            TrailColoring::Flat => LinearRgba::WHITE,
+            TrailColoring::Speed { slow, fast, max_speed } => {
+                let t = if max_speed > 0.0 { (speed / max_speed).clamp(0.0, 1.0) } else { 1.0 };
+                slow.mix(&fast, t as f32)
+            }
+        }
+    }
+}
+
+/// How trails are drawn over the scene, see `Config::trail_blend`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrailBlend {
+    /// Trails overwrite whatever is behind them, crossings included.
+    #[default]
+    Opaque,
+    /// Blended by the trail color's alpha, so crossings show through.
+    Alpha,
+    /// Added onto what's behind, scaled by alpha, so crossings brighten.
+    /// Trail colors are HDR, so overlaps quickly climb past the bloom
+    /// threshold: a crossing or a bunched-up slow stretch glows brighter and
+    /// wider than the trail around it, and many overlaps tonemap to white.
+    /// Lower the trail alpha in busy scenes.
+    Additive,
+}
+
+impl TrailBlend {
+    pub fn alpha_mode(self) -> AlphaMode {
+        match self {
+            TrailBlend::Opaque => AlphaMode::Opaque,
+            TrailBlend::Alpha => AlphaMode::Blend,
+            TrailBlend::Additive => AlphaMode::Add,
+        }
+    }
+}
+
+/// See `Config::orphaned_trails`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OrphanedTrails {
+    /// Despawned along with the mesh and material, the frame the body goes.
+    #[default]
+    Despawn,
+    /// Left where they were, frozen, until the next reset.
+    Keep,
+    /// Frozen and faded to transparent over this many seconds, then
+    /// despawned like `Despawn`. Without a positive duration they go at
+    /// once, as under `Despawn`.
+    FadeOut(f32),
+}
+
+/// How the segments of a ribbon trail meet at a turn, see
+/// `BodyConfig::trail_width`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrailJoin {
+    /// Extends the outer edges until they meet. Falls back to `Bevel`
+    /// where the tip would reach past `MITER_LIMIT` half widths, so sharp
+    /// turns don't spike.
+    Miter,
+    /// Cuts the outer corner straight across.
+    #[default]
+    Bevel,
+    /// Rounds the outer corner off with an arc.
+    Round,
+}
+
+/// Mesh used to render a body. Scaled by `BodyConfig::radius` either way.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum BodyShape {
+    /// The shared unit sphere; keeps bodies batched together.
+    #[default]
+    Sphere,
+    /// The shared unit cube.
+    Cube,
+    /// A user-supplied mesh. The handle is cloned into the body's config and
+    /// its mesh component, so the asset stays loaded until the body despawns
+    /// and the caller drops their own handle. Not saved to scenario files.
+    #[serde(skip)]
+    Custom(Handle<Mesh>),
 }
 
 impl Default for BodyConfig {
@@ -108,283 +710,1014 @@ impl Default for BodyConfig {
             position: DVec3::ZERO,
             velocity: DVec3::ZERO,
             color: None,
+            brightness: None,
             trail_color: None,
             trail_length: 100,
+            trail_min_spacing: None,
+            trail_spacing_mode: default(),
+            trail_coloring: TrailColoring::Flat,
+            trail_width: None,
+            trail_join: TrailJoin::Bevel,
+            shape: BodyShape::Sphere,
+            test_particle: false,
+            group: None,
+            anchored: false,
+            instance: None,
+            spin: None,
         }
     }
 }
 
+/// World position drawn at the render-space origin. `Transform`s and trail
+/// points are f32 offsets from it while `Position` stays absolute f64, so
+/// bodies near the origin render without jitter however large their
+/// coordinates. Follows the center of mass with `Config::floating_origin`.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct RenderOrigin(pub DVec3);
+
+impl RenderOrigin {
+    /// Where the world `position` is drawn.
+    pub fn to_render(&self, position: DVec3) -> Vec3 {
+        (position - self.0).as_vec3()
+    }
+
+    /// The world position drawn at render-space `point`.
+    pub fn to_world(&self, point: Vec3) -> DVec3 {
+        self.0 + point.as_dvec3()
+    }
+}
+
+/// Factor on `Config::timestep`, so presentation features such as
+/// `crate::cinematic::CinematicPlugin` can slow the simulation down without
+/// touching the saved config. Every step shrinks with it, which only ever
+/// makes the integration more accurate; it is unrelated to `substeps` and
+/// `step_stability`. Defaults to 1.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct TimeDilation(pub f64);
+
+impl Default for TimeDilation {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+impl TimeDilation {
+    /// Real seconds that `simulated` seconds take to play out under
+    /// `config`, slowed down by this factor.
+    pub fn real_seconds(self, config: &Config, simulated: f64) -> f64 {
+        simulated / (config.timestep * self.0)
+    }
+}
+
+/// Maps every live body's `BodyId` to its entity, ordered by id.
+#[derive(Resource, Default)]
+pub struct BodyIndex {
+    pub(crate) next_id: u32,
+    entities: BTreeMap<BodyId, Entity>,
+}
+
+impl BodyIndex {
+    pub fn get(&self, id: BodyId) -> Option<Entity> {
+        self.entities.get(&id).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (BodyId, Entity)> + '_ {
+        self.entities.iter().map(|(id, entity)| (*id, *entity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn next_id(&mut self) -> BodyId {
+        let id = BodyId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+}
+
+/// Simulation time advanced so far, counted in `FixedUpdate` ticks.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimClock {
+    /// Physics ticks run since startup or the last reset.
+    pub steps: u64,
+    /// Simulated seconds elapsed over those ticks.
+    pub elapsed: f64,
+}
+
+/// Bodies absorbed into others since startup, for `RunSummary::merged`.
+/// Evictions and despawns don't count.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct MergeCount(pub(crate) u64);
+
+/// Set while the simulation is paused because the window lost focus, so
+/// regaining focus only resumes pauses the user didn't make themselves.
+#[derive(Resource, Default)]
+struct AutoPaused(bool);
+
 #[derive(Resource)]
-pub struct BodyMesh(Handle<Mesh>);
+pub struct BodyMesh {
+    pub(crate) sphere: Handle<Mesh>,
+    cube: Handle<Mesh>,
+}
 
 // COMMANDS
-struct SpawnBodyCommand {
+/// Peak channel of golden-angle auto colors, about as bright as the
+/// demo scenario's bodies.
+const AUTO_COLOR_INTENSITY: f32 = 130.;
+
+/// Color for the `index`th uncolored body: the palette entry, wrapping
+/// around, or a step along a golden-angle hue walk when the palette is
+/// empty, so consecutive bodies never land near each other on the wheel.
+fn auto_color(palette: &[LinearRgba], index: usize) -> LinearRgba {
+    if !palette.is_empty() {
+        return palette[index % palette.len()];
+    }
+    const GOLDEN_ANGLE: f32 = 137.507_77;
+    let hue = (index as f32 * GOLDEN_ANGLE) % 360.;
+    let color = LinearRgba::from(Hsva::new(hue, 0.8, 1., 1.));
+    let peak = color.red.max(color.green).max(color.blue);
+    (color * (AUTO_COLOR_INTENSITY / peak)).with_alpha(1.)
+}
+
+/// `color` rescaled so its brightest channel is `brightness`, hue and
+/// alpha unchanged. Black stays black.
+fn with_brightness(color: LinearRgba, brightness: f32) -> LinearRgba {
+    let peak = color.red.max(color.green).max(color.blue);
+    if peak <= 0. {
+        return color;
+    }
+    let scale = brightness / peak;
+    LinearRgba::new(color.red * scale, color.green * scale, color.blue * scale, color.alpha)
+}
+
+pub(crate) struct SpawnBodyCommand {
     // you can have some parameters
-    body: BodyConfig,
+    /// Reserved up front so `spawn_body` can hand it back immediately.
+    pub(crate) entity: Entity,
+    pub(crate) body: BodyConfig,
+}
+
+/// `position` moved until it is at least `min_separation` from each of
+/// `others`, stepping away from the closest one still too near. Gives up
+/// after one step per body, which only a tight cluster needs.
+fn separate(mut position: DVec3, others: &[DVec3], min_separation: f64) -> DVec3 {
+    for _ in 0..=others.len() {
+        let closest = others.iter()
+            .map(|other| (other, position.distance_squared(*other)))
+            .filter(|(_, distance_sq)| *distance_sq < min_separation * min_separation)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        let Some((other, _)) = closest else {
+            break;
+        };
+        let away = (position - *other).try_normalize().unwrap_or(DVec3::X);
+        position = *other + away * min_separation;
+    }
+    position
 }
 
 impl Command for SpawnBodyCommand {
-    fn apply(self, world: &mut World) {
+    fn apply(mut self, world: &mut World) {
+        // Despawned again before the command ran.
+        if world.get_entity(self.entity).is_none() {
+            return;
+        }
         // Retrieve and store the necessary resources in local variables
-        let body_mesh = world.get_resource::<BodyMesh>().unwrap().0.clone();
-        let mut materials = world
-            .get_resource_mut::<Assets<StandardMaterial>>()
-            .unwrap();
-
-        let body_color = self.body.color.clone()
-            .unwrap_or(LinearRgba::rgb(150., 150., 150.));
-
-        let body_material = materials.add(StandardMaterial {
-            emissive: body_color,
-            ..default()
-        });
+        let config = world.resource::<Config>();
+        if self.body.mass < 0. && !config.allow_negative_mass {
+            warn!(
+                "refusing to spawn a body with negative mass {}; set `allow_negative_mass` to enable it",
+                self.body.mass,
+            );
+            world.despawn(self.entity);
+            return;
+        }
+        let coincident_spawns = config.coincident_spawns;
+        if coincident_spawns != CoincidentSpawns::Allow {
+            // Bodies of other instances never meet this one.
+            let instance = self.body.instance.map(|i| i.id);
+            let others: Vec<DVec3> = world
+                .query_filtered::<(&Position, Option<&SimInstance>), With<Body>>()
+                .iter(world)
+                .filter(|(_, other)| other.map(|i| i.id) == instance)
+                .map(|(position, _)| position.0)
+                .collect();
+            match coincident_spawns {
+                CoincidentSpawns::Nudge(min_separation) if min_separation > 0. => {
+                    self.body.position = separate(self.body.position, &others, min_separation);
+                }
+                CoincidentSpawns::Reject if others.contains(&self.body.position) => {
+                    warn!(
+                        "refusing to spawn a body at {}, where there already is one; see `coincident_spawns`",
+                        self.body.position,
+                    );
+                    world.despawn(self.entity);
+                    return;
+                }
+                _ => {}
+            }
+        }
+        let id = world.get_resource_or_insert_with(BodyIndex::default).next_id();
+        let config = world.resource::<Config>();
+        let body_color = self.body.color
+            .or_else(|| {
+                let palette = &config.group_palette;
+                let group = self.body.group? as usize;
+                (!palette.is_empty()).then(|| palette[group % palette.len()])
+            })
+            .or_else(|| {
+                let palette = config.auto_palette.as_ref()?;
+                Some(auto_color(palette, id.0 as usize))
+            })
+            .unwrap_or(if self.body.mass < 0. {
+                // Repulsive bodies default to a cool tint.
+                LinearRgba::rgb(20., 80., 150.)
+            } else {
+                LinearRgba::rgb(150., 150., 150.)
+            });
+        let body_color = match self.body.brightness {
+            Some(brightness) => with_brightness(body_color, brightness),
+            None => body_color,
+        };
 
-        world.spawn(BodyBundle {
+        let shadows = config.render.shadows;
+        let origin = world.get_resource::<RenderOrigin>().copied().unwrap_or_default();
+        let entity = self.entity;
+        world.entity_mut(entity).insert(BodyBundle {
             body: Body,
+            id,
             position: Position(self.body.position),
             velocity: Velocity(self.body.velocity),
             acceleration: Acceleration(DVec3::ZERO),
+            last_acceleration: LastAcceleration::default(),
             mass: Mass(self.body.mass),
-            mesh: PbrBundle {
-                mesh: body_mesh,
-                material: body_material,
-                transform: Transform {
-                    translation: self.body.position.as_vec3(),
-                    scale: Vec3::splat(self.body.radius as f32),
-                    ..default()
-                },
+            color: BodyColor(body_color),
+            spatial: SpatialBundle::from_transform(Transform {
+                translation: origin.to_render(self.body.position),
+                scale: Vec3::splat(self.body.radius as f32),
                 ..default()
-            },
+            }),
             config: self.body.clone(),
         });
+        // Headless worlds have no meshes or materials; the body still simulates.
+        if let Some(meshes) = world.get_resource::<BodyMesh>() {
+            let body_mesh = match &self.body.shape {
+                BodyShape::Sphere => meshes.sphere.clone(),
+                BodyShape::Cube => meshes.cube.clone(),
+                BodyShape::Custom(handle) => handle.clone(),
+            };
+            if let Some(mut materials) = world.get_resource_mut::<Assets<StandardMaterial>>() {
+                let body_material = materials.add(body_material(body_color, shadows));
+                world.entity_mut(entity).insert((body_mesh, body_material));
+            }
+        }
+        if self.body.test_particle {
+            world.entity_mut(entity).insert(TestParticle);
+        }
+        if self.body.anchored {
+            world.entity_mut(entity).insert(Anchored);
+        }
+        if let Some(instance) = self.body.instance {
+            world.entity_mut(entity).insert(instance);
+        }
+        world.resource_mut::<BodyIndex>().entities.insert(id, entity);
+        // Headless worlds may not register the event.
+        if let Some(mut events) = world.get_resource_mut::<Events<BodySpawned>>() {
+            events.send(BodySpawned { entity, config: self.body.clone() });
+        }
+        debug!(
+            id = id.0,
+            ?entity,
+            mass = self.body.mass,
+            position = ?self.body.position,
+            velocity = ?self.body.velocity,
+            "body spawned",
+        );
+    }
+}
+
+/// An emissive glow normally; with `RenderConfig::shadows`, a matte, lit
+/// surface in the same hue so shading and eclipses show.
+fn body_material(color: LinearRgba, shadows: bool) -> StandardMaterial {
+    if !shadows {
+        return StandardMaterial {
+            emissive: color,
+            ..default()
+        };
+    }
+    let peak = color.red.max(color.green).max(color.blue).max(1.);
+    StandardMaterial {
+        base_color: LinearRgba::rgb(color.red / peak, color.green / peak, color.blue / peak).into(),
+        perceptual_roughness: 0.8,
+        ..default()
     }
 }
 
 pub trait SpawnBodyCommandExt {
     // define a method that we will be able to call on `commands`
-    fn spawn_body(&mut self, body: &BodyConfig);
+    /// Queues `body` for spawning and returns its entity, which is usable
+    /// right away in further commands.
+    fn spawn_body(&mut self, body: &BodyConfig) -> Entity;
 }
 
 // implement our trait for Bevy's `Commands`
 impl<'w, 's> SpawnBodyCommandExt for Commands<'w, 's> {
-    fn spawn_body(&mut self, body: &BodyConfig) {
+    fn spawn_body(&mut self, body: &BodyConfig) -> Entity {
+        let entity = self.spawn_empty().id();
         self.add(SpawnBodyCommand {
+            entity,
             body: body.clone(),
         });
+        entity
+    }
+}
+
+/// Replaces the running scenario: despawns every body and trail, installs
+/// `config` and spawns its initial bodies. Meshes and the background built
+/// at startup are kept.
+pub struct ResetSimulation(pub Config);
+
+impl Command for ResetSimulation {
+    fn apply(self, world: &mut World) {
+        let bodies = self.0.spawned_initial_bodies();
+        reset_with_bodies(world, self.0, bodies);
     }
 }
 
+/// `ResetSimulation` spawning `bodies` in place of the config's own
+/// initial bodies. Returns the id each body got, `None` where it was
+/// refused.
+pub(crate) fn reset_with_bodies(world: &mut World, config: Config, bodies: Vec<BodyConfig>) -> Vec<Option<BodyId>> {
+    // Installed first so new bodies pick colors from the new palette.
+    clear_simulation(world, config);
+    let ids = bodies.into_iter()
+        .map(|body| {
+            let entity = world.spawn_empty().id();
+            SpawnBodyCommand { entity, body }.apply(world);
+            // Refused bodies are gone again.
+            let mut entity = world.get_entity_mut(entity)?;
+            entity.insert(InitialBody);
+            entity.get::<BodyId>().copied()
+        })
+        .collect();
+    rebase_origin(world);
+    ids
+}
+
+/// Despawns every body and trail, zeroes the id counter and clock, and
+/// installs `config`, reapplying its timestep if `GravityPlugin` owns it.
+pub(crate) fn clear_simulation(world: &mut World, mut config: Config) {
+    let bodies: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<Body>, With<Trail>)>>()
+        .iter(world)
+        .collect();
+    for entity in bodies {
+        despawn_with_children_recursive(world, entity);
+    }
+    world.insert_resource(BodyIndex::default());
+    world.insert_resource(SimClock::default());
+    config.check_step_stability();
+    if world.contains_resource::<ManagesFixedTimestep>() {
+        world.insert_resource(Time::<Fixed>::from_hz(config.physics_hz));
+        if let Some(mut time) = world.get_resource_mut::<Time<Virtual>>() {
+            time.set_max_delta(config.max_frame_delta());
+        }
+    }
+    world.insert_resource(config);
+}
+
+// SCHEDULES
+/// One integration step: force accumulation followed by the position update.
+/// Run `Config::substeps` times per `FixedUpdate` tick.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhysicsStep;
+
 // PLUGIN
-pub(crate) struct GravityPlugin {
+/// Runs the simulation described by a `Config`.
+///
+/// By default the plugin initializes `SimulationState` and `TrailState` and
+/// sets `Time<Fixed>` to `Config::physics_hz`, also on every
+/// `ResetSimulation`. Hosts that own those can opt out with
+/// `manage_states(false)` and `manage_fixed_timestep(false)`; the states
+/// must then be initialized by the host (e.g. with `init_state`) and the
+/// physics runs at whatever rate `FixedUpdate` ticks. It also adds
+/// `TrailPlugin`, unless built with `trails(false)`.
+pub struct GravityPlugin {
     config: Config,
+    manage_states: bool,
+    manage_fixed_timestep: bool,
+    trails: bool,
 }
 
 impl GravityPlugin {
     pub fn new(cfg: Config) -> Self {
-        Self { config: cfg }
+        Self { config: cfg, manage_states: true, manage_fixed_timestep: true, trails: true }
+    }
+
+    /// Whether the plugin initializes `SimulationState` and `TrailState`.
+    pub fn manage_states(mut self, manage: bool) -> Self {
+        self.manage_states = manage;
+        self
+    }
+
+    /// Whether the plugin adds `TrailPlugin`. Without it bodies get no
+    /// trails, and `TrailState` is left alone.
+    pub fn trails(mut self, trails: bool) -> Self {
+        self.trails = trails;
+        self
+    }
+
+    /// Whether the plugin sets `Time<Fixed>` from `Config::physics_hz`.
+    pub fn manage_fixed_timestep(mut self, manage: bool) -> Self {
+        self.manage_fixed_timestep = manage;
+        self
     }
 }
 
+/// Present when `GravityPlugin` owns `Time<Fixed>`, so `ResetSimulation`
+/// knows whether to reapply `Config::physics_hz`.
+#[derive(Resource)]
+struct ManagesFixedTimestep;
+
 impl Plugin for GravityPlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<SimulationState>()
-            .init_state::<TrailState>()
-            .insert_resource(self.config.clone())
-            .add_systems(Startup, (setup, spawn_initial_bodies).chain())
+        if self.manage_states {
+            app.init_state::<SimulationState>();
+        }
+        if self.trails {
+            app.add_plugins(TrailPlugin::default().manage_state(self.manage_states));
+        }
+        if self.manage_fixed_timestep {
+            app.insert_resource(Time::<Fixed>::from_hz(self.config.physics_hz))
+                .insert_resource(ManagesFixedTimestep)
+                .add_systems(Startup, limit_catch_up);
+        }
+        app.insert_resource(self.config.clone())
+            .init_resource::<BodyIndex>()
+            .init_resource::<SimClock>()
+            .init_resource::<MergeCount>()
+            .init_resource::<RenderOrigin>()
+            .init_resource::<AutoPaused>()
+            .init_resource::<TimeDilation>()
+            // Registered here too so the plugin also runs without a window.
+            .add_event::<WindowFocused>()
+            .add_event::<SimulationPaused>()
+            .add_event::<SimulationResumed>()
+            .add_event::<BodySpawned>()
+            .init_resource::<KeyBindings>()
+            .add_plugins(crate::encounters::EncounterPlugin)
+            .add_systems(Startup, (setup, check_step_stability, spawn_initial_bodies).chain())
+            .add_systems(PhysicsStep, (gravity, gw_inspiral.run_if(gw_inspiral_enabled), update_body).chain())
             .add_systems(
                 FixedUpdate,
-                (gravity, update_body)
-                    .run_if(in_state(SimulationState::Running))
+                (rebase_origin, run_physics_substeps)
                     .chain()
+                    .run_if(in_state(SimulationState::Running))
             )
             .add_systems(
-                Update,
-                (update_trail, draw_trail)
-                    .run_if(in_state(TrailState::Show))
-                    .run_if(in_state(SimulationState::Running))
-                    .after(update_body)
-                    .chain()
+                OnTransition { exited: SimulationState::Running, entered: SimulationState::Stopped },
+                send_paused,
             )
+            .add_systems(
+                OnTransition { exited: SimulationState::Stopped, entered: SimulationState::Running },
+                send_resumed,
+            )
+            .add_systems(Last, save_on_exit.run_if(on_event::<AppExit>()))
             .add_systems(Update,(
-                toggle_simulation,
-                toggle_trail,
-                spawn_on_click.run_if(input_just_pressed(MouseButton::Left))
+                toggle_simulation.run_if(action_just_pressed(Action::ToggleSimulation)),
+                pause_on_focus_change.run_if(on_event::<WindowFocused>()),
+                sync_body_material,
+                spawn_on_click
+                    .run_if(action_just_pressed(Action::SpawnOrDrag))
+                    .run_if(click_spawn_allowed),
+                spawn_at_center_of_mass
+                    .run_if(action_just_pressed(Action::SpawnAtCenterOfMass))
+                    .run_if(resource_exists::<CenterOfMass>),
             ));
     }
 }
 
 // SYSTEMS
-fn setup(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
-    commands.insert_resource(
-        BodyMesh(meshes.add(Sphere::new(1.0).mesh().ico(3).unwrap()))
-    )
+fn setup(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, config: Res<Config>) {
+    commands.insert_resource(BodyMesh {
+        sphere: meshes.add(body_mesh(config.body_mesh_detail)),
+        cube: meshes.add(Cuboid::from_length(2.0)),
+    })
+}
+
+/// Builds the unit sphere shared by all bodies, falling back to a UV sphere
+/// if the requested subdivision level is too high for an icosphere.
+fn body_mesh(detail: u8) -> Mesh {
+    Sphere::new(1.0).mesh().ico(detail as usize).unwrap_or_else(|err| {
+        warn!("invalid body_mesh_detail {detail}: {err}, falling back to a UV sphere");
+        Sphere::new(1.0).mesh().uv(32, 18)
+    })
+}
+
+fn limit_catch_up(config: Res<Config>, mut time: ResMut<Time<Virtual>>) {
+    time.set_max_delta(config.max_frame_delta());
+}
+
+fn check_step_stability(mut config: ResMut<Config>) {
+    config.check_step_stability();
 }
 
 fn spawn_initial_bodies(
     mut commands: Commands,
     config: Res<Config>,
 ) {
-    for body in config.initial_bodies.iter() {
-        commands.spawn_body(body);
+    for body in config.spawned_initial_bodies().iter() {
+        let entity = commands.spawn_body(body);
+        commands.entity(entity).try_insert(InitialBody);
     }
 }
 
+/// What `make_room` needs to find the oldest body.
+pub(crate) type BodyAges<'w, 's> = Query<'w, 's, (Entity, &'static BodyId, Has<InitialBody>), With<Body>>;
+
 fn spawn_on_click(
     mut commands: Commands,
-    input: Res<ButtonInput<MouseButton>>,
-    cursor: Res<crate::cursor::CursorCoords>
+    cursor: Res<crate::cursor::CursorCoords>,
+    config: Res<Config>,
+    bodies: Query<(Entity, &Position, &BodyConfig), With<Body>>,
+    ages: BodyAges,
+) {
+    // Clicks on an existing body are for picking it up, not spawning.
+    if crate::picking::body_at(cursor.0, bodies.iter()).is_some() {
+        return;
+    }
+    if !make_room(&mut commands, &config, &ages) {
+        return;
+    }
+    commands.spawn_body(&BodyConfig {
+        position: DVec3::from((cursor.0,0.)),
+        ..config.click_body_template.clone()
+    });
+}
+
+/// Drops `Config::click_body_template` onto the current `CenterOfMass`, e.g.
+/// to anchor a new central star. Does nothing while there is none.
+fn spawn_at_center_of_mass(
+    mut commands: Commands,
+    center: Res<CenterOfMass>,
+    config: Res<Config>,
+    ages: BodyAges,
+) {
+    let Some(position) = center.0 else {
+        return;
+    };
+    if !make_room(&mut commands, &config, &ages) {
+        return;
+    }
+    commands.spawn_body(&BodyConfig {
+        position,
+        ..config.click_body_template.clone()
+    });
+}
+
+/// Despawns the oldest evictable bodies until one more fits under
+/// `Config::max_bodies`, so a cap lowered at runtime is restored by the next
+/// spawn. `false`, evicting nothing, if too few bodies may be evicted.
+pub(crate) fn make_room(commands: &mut Commands, config: &Config, ages: &BodyAges) -> bool {
+    let Some(max_bodies) = config.max_bodies else {
+        return true;
+    };
+    let excess = (ages.iter().len() + 1).saturating_sub(max_bodies);
+    if excess == 0 {
+        return true;
+    }
+    let mut evictable: Vec<(BodyId, Entity)> = ages.iter()
+        .filter(|(_, _, initial)| config.evict_initial_bodies || !initial)
+        .map(|(entity, id, _)| (*id, entity))
+        .collect();
+    if evictable.len() < excess {
+        return false;
+    }
+    // Ids are handed out in spawn order, so the lowest are the oldest.
+    evictable.sort_unstable();
+    for (_, entity) in evictable.into_iter().take(excess) {
+        commands.entity(entity).despawn_recursive();
+    }
+    true
+}
+
+/// Advances the simulation in `world` by `steps` fixed ticks, as if
+/// `FixedUpdate` had run that often while `Running`, with each tick lasting
+/// `1 / Config::physics_hz` and split into `Config::substeps`.
+///
+/// Needs no app, schedule or rendering resources. The world must hold a
+/// `Config`, and bodies spawned through `ResetSimulation` or `spawn_body`;
+/// `ResetSimulation(config).apply(&mut world)` on an empty world provides
+/// both. Trails, encounters and other `FixedUpdate` systems don't run.
+pub fn step(world: &mut World, steps: usize) {
+    if !world.get_resource_or_insert_with(Schedules::default).contains(PhysicsStep) {
+        let mut schedule = Schedule::new(PhysicsStep);
+        schedule.add_systems((gravity, gw_inspiral.run_if(gw_inspiral_enabled), update_body).chain());
+        world.add_schedule(schedule);
+    }
+    world.init_resource::<SimClock>();
+    world.init_resource::<MergeCount>();
+    world.init_resource::<RenderOrigin>();
+    world.init_resource::<TimeDilation>();
+    let tick = Duration::from_secs_f64(1. / world.resource::<Config>().physics_hz);
+    let saved_time = world.remove_resource::<Time>();
+    for _ in 0..steps {
+        let mut time = Time::<()>::default();
+        time.advance_by(tick);
+        world.insert_resource(time);
+        rebase_origin(world);
+        run_physics_substeps(world);
+    }
+    world.remove_resource::<Time>();
+    if let Some(time) = saved_time {
+        world.insert_resource(time);
+    }
+}
+
+/// Runs the app's `FixedMain` schedules (and so `FixedUpdate`) `steps`
+/// times back to back, each as one tick of `Time<Fixed>`'s timestep,
+/// without waiting for wall-clock time to accumulate. Returns a summary of
+/// how the bodies fared.
+///
+/// Unlike `step`, every fixed-timestep system the app registered runs, so
+/// trails, encounters and exports advance too, and a run of `n` ticks here
+/// follows the same trajectory as `n` ticks in real time. `Update` and
+/// the render schedules are skipped, as are state transitions: set
+/// `SimulationState::Running` and call `app.update()` once beforehand so
+/// `Startup` has spawned the bodies and the state has been applied.
+pub fn run_steps(app: &mut App, steps: usize) -> RunSummary {
+    let world = app.world_mut();
+    let start = RunStart::capture(world);
+    for _ in 0..steps {
+        let mut fixed = world.resource_mut::<Time<Fixed>>();
+        let timestep = fixed.timestep();
+        fixed.advance_by(timestep);
+        let generic = fixed.as_generic();
+        *world.resource_mut::<Time>() = generic;
+        world.run_schedule(bevy::app::FixedMain);
+    }
+    let generic = world.resource::<Time<Virtual>>().as_generic();
+    *world.resource_mut::<Time>() = generic;
+    start.finish(world)
+}
+
+pub(crate) fn run_physics_substeps(world: &mut World) {
+    let config = world.resource::<Config>();
+    let substeps = config.substeps.max(1);
+    let dilation = world.get_resource::<TimeDilation>().copied().unwrap_or_default().0;
+    let dt = world.resource::<Time>().delta_seconds_f64() * config.timestep * dilation;
+    for _ in 0..substeps {
+        world.run_schedule(PhysicsStep);
+    }
+    let mut clock = world.resource_mut::<SimClock>();
+    clock.steps += 1;
+    clock.elapsed += dt;
+}
+
+/// With `Config::floating_origin`, moves `RenderOrigin` to the center of
+/// mass and shifts every body, trail point, the starfield and the camera
+/// with it, so nothing visibly moves.
+pub(crate) fn rebase_origin(world: &mut World) {
+    if !world.resource::<Config>().floating_origin {
+        return;
+    }
+    let (total_mass, weighted) = world
+        .query_filtered::<(&Mass, &Position), (With<Body>, Without<TestParticle>)>()
+        .iter(world)
+        .fold((0., DVec3::ZERO), |(total, weighted), (mass, position)| {
+            (total + mass.0, weighted + mass.0 * position.0)
+        });
+    if total_mass <= 0. {
+        return;
+    }
+    let mut origin = world.get_resource_or_insert_with(RenderOrigin::default);
+    let shift = (weighted / total_mass - origin.0).as_vec3();
+    origin.0 = weighted / total_mass;
+    if shift == Vec3::ZERO {
+        return;
+    }
+    let mut moved = world.query_filtered::<
+        &mut Transform,
+        Or<(With<Body>, With<crate::cursor::MainCamera>, With<crate::render::Starfield>)>,
+    >();
+    for mut transform in moved.iter_mut(world) {
+        transform.translation -= shift;
+    }
+    for mut trail in world.query::<&mut Trail>().iter_mut(world) {
+        trail.shift(-shift);
+    }
+}
+
+fn click_spawn_allowed(config: Res<Config>) -> bool {
+    config.allow_click_spawn && config.drag_launch.is_none()
+}
+
+/// The force law `Config` describes.
+pub(crate) fn force_law(config: &Config) -> ForceLaw {
+    ForceLaw::new(
+        config.gravitational_constant(),
+        config.force_exponent,
+        config.gravity_cutoff,
+        config.extended_bodies,
+    )
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn gravity(
+    mut query: Query<(Entity, &Mass, &Position, &BodyConfig, &mut Acceleration, Has<TestParticle>, Option<&SimInstance>), With<Body>>,
+    config: Res<Config>,
 ) {
-    if input.just_pressed(MouseButton::Left) {
-       commands.spawn_body(&BodyConfig {
-           radius: 0.2,
-           mass: 0.2,
-           position: DVec3::from((cursor.0.as_dvec2(),0.)),
-           velocity: DVec3::ZERO,
-           color: Some(LinearRgba::rgb(5., 5., 5.)),
-           trail_color: Some(LinearRgba::new(1., 1., 1., 0.4)),
-           trail_length: 20,
-       });
-    }
-}
-
-fn gravity(mut query: Query<(&Mass, &GlobalTransform, &mut Acceleration), With<Body>>) {
-    let mut iter = query.iter_combinations_mut();
-    while let Some(
-        [
-           (m1, t1, mut a1),
-           (m2, t2, mut a2)
-       ]
-    ) = iter.fetch_next() {
-        let delta = t2.translation().as_dvec3() - t1.translation().as_dvec3();
-        let distance_sq = delta.length_squared();
-
-        if distance_sq == 0.0 {
+    let law = force_law(&config);
+    let mean_field_cutoff = config.mean_field_cutoff.filter(|cutoff| *cutoff > 0.);
+    if config.parallel_gravity && mean_field_cutoff.is_none() {
+        parallel_accelerations(&mut query, &law, config.gravity_anisotropy.unwrap_or(DVec3::ONE));
+        return;
+    }
+    // Instances don't interact, so each is summed on its own. The pairs
+    // within one come in query order either way.
+    let mut instances: BTreeMap<Option<u32>, Instance> = BTreeMap::new();
+    for (_, mass, position, config, acceleration, test, instance) in query.iter() {
+        let bodies = instances.entry(instance.map(|i| i.id)).or_default();
+        // Read the f64 positions so every substep sees the latest state.
+        bodies.positions.push(position.0);
+        // Test particles are sources of no gravity.
+        bodies.masses.push(if test { 0. } else { mass.0 });
+        bodies.radii.push(config.radius);
+        bodies.accelerations.push(acceleration.0);
+    }
+    for bodies in instances.values_mut() {
+        let Some(cutoff) = mean_field_cutoff else {
+            crate::nbody::accumulate_pairwise(&law, &bodies.positions, &bodies.masses, &bodies.radii, &mut bodies.accelerations);
             continue;
+        };
+        let mut near = SpatialGrid::from_points(cutoff, &bodies.positions).candidate_pairs(cutoff);
+        // The grid comes out in hash order; sorted, the sums are reproducible.
+        near.sort_unstable();
+        crate::nbody::accumulate_mean_field(
+            &law,
+            cutoff,
+            &near,
+            &bodies.positions,
+            &bodies.masses,
+            &bodies.radii,
+            &mut bodies.accelerations,
+        );
+    }
+    let mut written: BTreeMap<Option<u32>, usize> = BTreeMap::new();
+    for (.., mut acceleration, _, instance) in query.iter_mut() {
+        let key = instance.map(|i| i.id);
+        let index = written.entry(key).or_default();
+        let total = instances[&key].accelerations[*index];
+        acceleration.0 = match config.gravity_anisotropy {
+            // Only the part gravity added is scaled.
+            Some(scale) => acceleration.0 + (total - acceleration.0) * scale,
+            None => total,
+        };
+        *index += 1;
+    }
+}
+
+/// One `SimInstance`'s bodies, flattened for `crate::nbody`.
+#[derive(Default)]
+struct Instance {
+    positions: Vec<DVec3>,
+    masses: Vec<f64>,
+    radii: Vec<f64>,
+    accelerations: Vec<DVec3>,
+}
+
+/// `Config::parallel_gravity`: every body sums the pull of all sources on
+/// its own, spread over the compute task pool by `par_iter_mut`. Each pair
+/// is evaluated from both ends, twice the work of the pairwise loop, but no
+/// two tasks write the same body. A body always sums its sources in query
+/// order, so results don't depend on the thread count or scheduling; they
+/// round differently from the pairwise loop, though, so the two drift apart
+/// in chaotic scenes. `anisotropy` is `Config::gravity_anisotropy`.
+#[allow(clippy::type_complexity)]
+fn parallel_accelerations(
+    query: &mut Query<(Entity, &Mass, &Position, &BodyConfig, &mut Acceleration, Has<TestParticle>, Option<&SimInstance>), With<Body>>,
+    law: &ForceLaw,
+    anisotropy: DVec3,
+) {
+    // Test particles are sources of no gravity.
+    let mut sources: BTreeMap<Option<u32>, Instance> = BTreeMap::new();
+    for (_, mass, position, config, _, test, instance) in query.iter() {
+        if test {
+            continue;
+        }
+        let bodies = sources.entry(instance.map(|i| i.id)).or_default();
+        bodies.positions.push(position.0);
+        bodies.masses.push(mass.0);
+        bodies.radii.push(config.radius);
+    }
+    // Headless worlds stepped outside an app have no pool yet.
+    ComputeTaskPool::get_or_init(TaskPool::default);
+    query.par_iter_mut().for_each(|(_, _, position, config, mut acceleration, _, instance)| {
+        // A body's own entry is at zero distance, which adds nothing.
+        if let Some(sources) = sources.get(&instance.map(|i| i.id)) {
+            acceleration.0 += crate::nbody::acceleration_at(
+                law,
+                position.0,
+                config.radius,
+                &sources.positions,
+                &sources.masses,
+                &sources.radii,
+            ) * anisotropy;
         }
+    });
+}
 
-        let f = G / distance_sq;
-        let force_unit_mass = delta * f;
-        a1.0 += force_unit_mass * m2.0;
-        a2.0 -= force_unit_mass * m1.0;
+fn gw_inspiral_enabled(config: Res<Config>) -> bool {
+    config.gw_inspiral
+}
+
+/// `Config::gw_inspiral`: damps the relative velocity of the dominant pair
+/// just enough to dissipate energy at the quoted `da/dt` on a circular
+/// orbit, split by mass so total momentum is kept. Once the two touch they
+/// merge into the heavier one, conserving mass and momentum; point masses
+/// passing through each other would otherwise fling apart.
+#[allow(clippy::type_complexity)]
+fn gw_inspiral(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<Config>,
+    mut merges: ResMut<MergeCount>,
+    dilation: Res<TimeDilation>,
+    mut query: Query<(
+        Entity,
+        &mut Mass,
+        &mut Position,
+        &mut Velocity,
+        &mut BodyConfig,
+        &mut Transform,
+        &mut Acceleration,
+    ), (With<Body>, Without<TestParticle>)>,
+) {
+    let mut bodies: Vec<_> = query.iter_mut().filter(|(_, mass, ..)| mass.0 > 0.).collect();
+    bodies.sort_by(|(_, a, ..), (_, b, ..)| b.0.total_cmp(&a.0));
+    let [(e1, m1, p1, v1, c1, t1, a1), (e2, m2, p2, v2, c2, _, a2), ..] = &mut bodies[..] else {
+        return;
+    };
+    let g = config.gravitational_constant();
+    let total = m1.0 + m2.0;
+    let separation = p1.0.distance(p2.0);
+    let relative_velocity = v2.0 - v1.0;
+    if relative_velocity.length_squared() / 2. >= g * total / separation {
+        return;
+    }
+    if separation <= c1.radius + c2.radius {
+        p1.0 = (p1.0 * m1.0 + p2.0 * m2.0) / total;
+        v1.0 = (v1.0 * m1.0 + v2.0 * m2.0) / total;
+        m1.0 = total;
+        c1.radius = (c1.radius.powi(3) + c2.radius.powi(3)).cbrt();
+        t1.scale = Vec3::splat(c1.radius as f32);
+        commands.entity(*e2).despawn_recursive();
+        merges.0 += 1;
+        debug!(survivor = ?e1, absorbed = ?e2, mass = total, "inspiral merged the dominant pair");
+        return;
     }
+    // On a circular orbit, damping the relative velocity at rate
+    // k / (2 a⁴) loses energy exactly as fast as `da/dt = -k / a³` does.
+    let k = 64. / 5. * g.powi(3) * m1.0 * m2.0 * total / config.gw_light_speed.powi(5);
+    let dt = time.delta_seconds_f64() * config.timestep * dilation.0 / config.substeps.max(1) as f64;
+    // Never remove more than half the relative velocity in one step.
+    let damping = (k / (2. * separation.powi(4))).min(0.5 / dt);
+    a1.0 += relative_velocity * damping * m2.0 / total;
+    a2.0 -= relative_velocity * damping * m1.0 / total;
 }
 
-fn update_body(
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_body(
     time: Res<Time>,
-    mut query: Query<(&mut Acceleration, &mut Transform, &mut Position, &mut Velocity), With<Body>>,
+    mut query: Query<(
+        &mut Acceleration,
+        &mut LastAcceleration,
+        &mut Transform,
+        &mut Position,
+        &mut Velocity,
+        Has<Anchored>,
+        Option<&SimInstance>,
+    ), With<Body>>,
     config: Res<Config>,
+    dilation: Res<TimeDilation>,
+    origin: Res<RenderOrigin>,
 ) {
-    let dt = time.delta_seconds_f64() * config.timestep;
+    let dt = time.delta_seconds_f64() * config.timestep * dilation.0 / config.substeps.max(1) as f64;
     for (
         mut a,
+        mut last,
         mut t,
         mut p,
-        mut v
+        mut v,
+        anchored,
+        instance,
     ) in query.iter_mut() {
-        v.0 += a.0 * dt;
-        p.0 += v.0 * dt;
+        last.0 = a.0;
+        if anchored {
+            a.0 = DVec3::ZERO;
+            continue;
+        }
+        let drag = v.0 * config.drag_coefficient;
+        let integrator = instance.and_then(|i| i.integrator).unwrap_or(config.integrator);
+        integrator.step(&mut p.0, &mut v.0, a.0 - drag, dt, config.max_speed);
         a.0 = DVec3::ZERO;
-        t.translation = p.0.as_vec3();
+        t.translation = origin.to_render(p.0);
     }
 }
 
-fn update_trail(
-    mut query: Query<(&Position, &TrailRef), With<Body>>,
-    mut trail_query: Query<&mut Trail, With<Trail>>,
+fn save_on_exit(
+    config: Res<Config>,
+    query: Query<(&BodyConfig, &Position, &Velocity, &Mass), With<Body>>,
 ) {
-    for (pos, trail_entity) in query.iter_mut() {
-        if let Ok(mut trail) = trail_query.get_mut(trail_entity.0) {
-            if trail.points.len() >= trail.max_length {
-                trail.points.remove(0);
-            }
-            trail.points.push(pos.0.as_vec3());
-        }
+    if !config.save_on_exit {
+        return;
+    }
+    let Some(path) = config.save_path.as_ref().or(config.source_path.as_ref()) else {
+        warn!("save_on_exit is set but there is no save_path or loaded scenario to write to");
+        return;
+    };
+
+    // Use the f64 physics state rather than the f32 transforms.
+    let mut snapshot = config.clone();
+    // The ensemble copies are saved as bodies, so they aren't copied again.
+    snapshot.ensemble = None;
+    snapshot.initial_bodies = query.iter()
+        .map(|(body, position, velocity, mass)| BodyConfig {
+            position: position.0,
+            velocity: velocity.0,
+            mass: mass.0,
+            ..body.clone()
+        })
+        .collect();
+
+    match snapshot.save(path) {
+        Ok(()) => info!("saved simulation state to {}", path.display()),
+        Err(err) => error!("failed to save simulation state to {}: {err}", path.display()),
     }
 }
 
-fn draw_trail(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut query: Query<(Entity, &BodyConfig, &GlobalTransform, Option<&TrailRef>), With<Body>>,
-    mut trail_entity_query: Query<(&Trail, &Handle<Mesh>, &Handle<StandardMaterial>), With<Trail>>,
+/// Rebuilds a body's material after its `BodyColor` changed, so recoloring
+/// takes effect immediately.
+#[allow(clippy::type_complexity)]
+fn sync_body_material(
+    config: Res<Config>,
+    materials: Option<ResMut<Assets<StandardMaterial>>>,
+    query: Query<(&BodyColor, &Handle<StandardMaterial>), (With<Body>, Changed<BodyColor>)>,
 ) {
-    for (
-        body, config, transform, trail
-    ) in query.iter_mut() {
-        if let Some(trail_entity) = trail {
-            if let Ok(
-                (trail, trail_mesh_handle, _trail_material_handle)
-            ) = trail_entity_query.get_mut(trail_entity.0) {
-                let trail_mesh = meshes.get_mut(trail_mesh_handle).unwrap();
-                let positions: Vec<[f32; 3]> = trail.points
-                    .iter()
-                    .map(|p| p.to_array())
-                    .collect();
-                trail_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-                continue;
-            } else {
-                commands.entity(trail_entity.0).despawn_recursive();
-            }
+    let Some(mut materials) = materials else {
+        return;
+    };
+    for (color, handle) in query.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            *material = body_material(color.0, config.render.shadows);
         }
-        let trail_color = config.trail_color.clone()
-            .unwrap_or(config.color.clone()
-                .unwrap_or(LinearRgba::rgb(150., 150., 150.)));
-        let trail_material_handle = materials.add(StandardMaterial {
-            emissive: trail_color,
-            ..default()
-        });
-
-        // Create the trail mesh
-        let mut trail_mesh = Mesh::new(
-            PrimitiveTopology::LineStrip,
-            RenderAssetUsages::default()
-        );
-        let trail_positions: Vec<Vec3> = vec![config.position.as_vec3()];
-        trail_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, trail_positions.clone());
-        let trail_mesh_handle = meshes.add(trail_mesh);
-
-        let trail = commands.spawn(TrailBundle {
-            trail: Trail {
-                max_length: config.trail_length,
-                points: trail_positions.clone(),
-            },
-            decay: TrailDecay(Duration::new(1, 0)),
-            color: TrailColor(trail_color.into()),
-            mesh: PbrBundle {
-                mesh: trail_mesh_handle,
-                material: trail_material_handle,
-                global_transform: *transform,
-                ..default()
-            }
-        }).id();
-
-        commands.get_entity(body).unwrap().insert(TrailRef(trail));
     }
 }
 
 fn toggle_simulation(
     state: Res<State<SimulationState>>,
     mut next_state: ResMut<NextState<SimulationState>>,
-    input: Res<ButtonInput<KeyCode>>,
+    mut auto_paused: ResMut<AutoPaused>,
 ) {
-    if input.just_pressed(KeyCode::Space) {
-        match state.get() {
-            SimulationState::Running => next_state.set(SimulationState::Stopped),
-            SimulationState::Stopped => next_state.set(SimulationState::Running),
-        }
+    // A manual toggle takes over from any automatic pause.
+    auto_paused.0 = false;
+    match state.get() {
+        SimulationState::Running => next_state.set(SimulationState::Stopped),
+        SimulationState::Stopped => next_state.set(SimulationState::Running),
     }
 }
 
-fn toggle_trail(
-    state: Res<State<TrailState>>,
-    mut next_state: ResMut<NextState<TrailState>>,
-    input: Res<ButtonInput<KeyCode>>,
+fn send_paused(mut events: EventWriter<SimulationPaused>) {
+    events.send(SimulationPaused);
+}
+
+fn send_resumed(mut events: EventWriter<SimulationResumed>) {
+    events.send(SimulationResumed);
+}
+
+fn pause_on_focus_change(
+    config: Res<Config>,
+    state: Res<State<SimulationState>>,
+    mut next_state: ResMut<NextState<SimulationState>>,
+    mut auto_paused: ResMut<AutoPaused>,
+    mut events: EventReader<WindowFocused>,
+    primary: Query<(), With<PrimaryWindow>>,
 ) {
-    if input.just_pressed(KeyCode::KeyT) {
-        match state.get() {
-            TrailState::Show => next_state.set(TrailState::Hide),
-            TrailState::Hide => next_state.set(TrailState::Show),
+    for event in events.read().filter(|event| primary.contains(event.window)) {
+        match (event.focused, state.get()) {
+            (false, SimulationState::Running) if config.pause_on_focus_loss => {
+                next_state.set(SimulationState::Stopped);
+                auto_paused.0 = true;
+            }
+            (true, SimulationState::Stopped) if auto_paused.0 && config.resume_on_focus => {
+                next_state.set(SimulationState::Running);
+                auto_paused.0 = false;
+            }
+            _ => {}
         }
     }
 }
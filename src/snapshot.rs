@@ -0,0 +1,196 @@
+use std::fs;
+use std::path::Path;
+use bevy::ecs::world::Command;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::scenario::ScenarioError;
+use crate::simulation::{
+    clear_simulation, Body, BodyColor, BodyConfig, BodyId, BodyIndex, Config, InitialBody, Mass,
+    Position, RenderOrigin, SimClock, SpawnBodyCommand, Velocity,
+};
+use crate::trail::{trail_bundle, Trail, TrailOwner, TrailRef};
+
+/// The whole live state of a simulation, unlike `Config` which only
+/// describes how it starts: every body's current state under its original
+/// id, the trail buffers, the clock and the render origin. Taken with
+/// `snapshot` and brought back exactly with `RestoreSnapshot`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SimulationSnapshot {
+    /// The running config, `initial_bodies` included, so later resets
+    /// still go back to the scenario's start.
+    pub config: Config,
+    pub clock: SimClock,
+    pub origin: DVec3,
+    /// Id the next spawned body gets.
+    pub next_id: u32,
+    pub bodies: Vec<BodySnapshot>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BodySnapshot {
+    pub id: BodyId,
+    /// The body's settings with `position`, `velocity` and `mass` replaced
+    /// by their live values. Colors are derived from it again on restore.
+    pub body: BodyConfig,
+    /// Whether it came from `Config::initial_bodies`.
+    pub initial: bool,
+    pub trail: Option<TrailSnapshot>,
+}
+
+/// A trail's points in render space, relative to `SimulationSnapshot::origin`,
+/// and the body's speed at each.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrailSnapshot {
+    pub points: Vec<Vec3>,
+    pub speeds: Vec<f32>,
+}
+
+impl SimulationSnapshot {
+    /// Writes the snapshot as RON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ScenarioError> {
+        let ron = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+        fs::write(path, ron)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        Ok(ron::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Captures the state of the simulation in `world`, see `SimulationSnapshot`.
+#[allow(clippy::type_complexity)]
+pub fn snapshot(world: &mut World) -> SimulationSnapshot {
+    let mut trails = world.query::<&Trail>();
+    let mut bodies = world.query_filtered::<(
+        &BodyConfig,
+        &Position,
+        &Velocity,
+        &Mass,
+        Has<InitialBody>,
+        Option<&TrailRef>,
+    ), With<Body>>();
+    let index = world.resource::<BodyIndex>();
+    let snapshots = index.iter()
+        .filter_map(|(id, entity)| {
+            let (body, position, velocity, mass, initial, trail) = bodies.get(world, entity).ok()?;
+            let trail = trail
+                .and_then(|trail| trails.get(world, trail.0).ok())
+                .map(|trail| TrailSnapshot { points: trail.points.clone(), speeds: trail.speeds.clone() });
+            Some(BodySnapshot {
+                id,
+                body: BodyConfig {
+                    position: position.0,
+                    velocity: velocity.0,
+                    mass: mass.0,
+                    ..body.clone()
+                },
+                initial,
+                trail,
+            })
+        })
+        .collect();
+    SimulationSnapshot {
+        config: world.resource::<Config>().clone(),
+        clock: world.get_resource::<SimClock>().copied().unwrap_or_default(),
+        origin: world.get_resource::<RenderOrigin>().copied().unwrap_or_default().0,
+        next_id: index.next_id,
+        bodies: snapshots,
+    }
+}
+
+/// Replaces the running simulation with a `SimulationSnapshot`: despawns
+/// every body and trail and respawns the snapshot's under their old ids.
+/// The origin is restored rather than recomputed, so trail points land
+/// exactly where they were. Trails are only rebuilt where meshes exist.
+pub struct RestoreSnapshot(pub SimulationSnapshot);
+
+impl Command for RestoreSnapshot {
+    fn apply(self, world: &mut World) {
+        let snapshot = self.0;
+        clear_simulation(world, snapshot.config);
+        world.insert_resource(snapshot.clock);
+        world.insert_resource(RenderOrigin(snapshot.origin));
+        for body in snapshot.bodies {
+            world.resource_mut::<BodyIndex>().next_id = body.id.0;
+            let entity = world.spawn_empty().id();
+            SpawnBodyCommand { entity, body: body.body }.apply(world);
+            let Some(mut spawned) = world.get_entity_mut(entity) else {
+                continue;
+            };
+            if body.initial {
+                spawned.insert(InitialBody);
+            }
+            if let Some(trail) = body.trail {
+                restore_trail(world, entity, trail);
+            }
+        }
+        world.resource_mut::<BodyIndex>().next_id = snapshot.next_id;
+    }
+}
+
+fn restore_trail(world: &mut World, body: Entity, trail: TrailSnapshot) {
+    if !world.contains_resource::<Assets<Mesh>>() {
+        return;
+    }
+    let blend = world.resource::<Config>().trail_blend;
+    let (config, color, transform) = {
+        let entity = world.entity(body);
+        let (Some(config), Some(color)) = (entity.get::<BodyConfig>(), entity.get::<BodyColor>()) else {
+            return;
+        };
+        let transform = entity.get::<GlobalTransform>().copied().unwrap_or_default();
+        (config.clone(), color.0, transform)
+    };
+    world.resource_scope(|world, mut meshes: Mut<Assets<Mesh>>| {
+        let Some(mut materials) = world.get_resource_mut::<Assets<StandardMaterial>>() else {
+            return;
+        };
+        let bundle = trail_bundle(
+            &config,
+            color,
+            trail.points,
+            trail.speeds,
+            transform,
+            blend,
+            &mut meshes,
+            &mut materials,
+        );
+        let trail = world.spawn((bundle, TrailOwner(body))).id();
+        world.entity_mut(body).insert(TrailRef(trail));
+    });
+}
+
+/// The snapshot `Action::SaveSnapshot` took last.
+#[derive(Resource, Default)]
+pub struct SavedSnapshot(pub Option<SimulationSnapshot>);
+
+/// Keybinds to remember the current state and return to it later.
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyBindings>()
+            .init_resource::<SavedSnapshot>()
+            .add_systems(Update, (
+                save_snapshot.run_if(action_just_pressed(Action::SaveSnapshot)),
+                restore_snapshot.run_if(action_just_pressed(Action::RestoreSnapshot)),
+            ));
+    }
+}
+
+fn save_snapshot(world: &mut World) {
+    let taken = snapshot(world);
+    info!("saved a snapshot of {} bodies at step {}", taken.bodies.len(), taken.clock.steps);
+    world.resource_mut::<SavedSnapshot>().0 = Some(taken);
+}
+
+fn restore_snapshot(mut commands: Commands, saved: Res<SavedSnapshot>) {
+    match &saved.0 {
+        Some(snapshot) => commands.add(RestoreSnapshot(snapshot.clone())),
+        None => warn!("no snapshot saved yet"),
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::math::{DVec3, IVec3};
+use bevy::utils::HashMap;
+
+/// Uniform hash grid over body positions, used to find nearby pairs without
+/// testing every combination.
+pub struct SpatialGrid {
+    cell_size: f64,
+    cells: HashMap<IVec3, Vec<usize>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f64) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    /// Builds a grid holding each point under its index in `points`.
+    pub fn from_points(cell_size: f64, points: &[DVec3]) -> Self {
+        let mut grid = Self::new(cell_size);
+        for (index, point) in points.iter().enumerate() {
+            grid.insert(index, *point);
+        }
+        grid
+    }
+
+    pub fn insert(&mut self, index: usize, point: DVec3) {
+        self.cells.entry(self.cell(point)).or_default().push(index);
+    }
+
+    fn cell(&self, point: DVec3) -> IVec3 {
+        (point / self.cell_size).floor().as_ivec3()
+    }
+
+    /// Returns every pair `(i, j)` with `i < j` whose points may lie within
+    /// `reach` of each other. Pairs further apart than `reach` can be
+    /// returned too; callers still need to check the exact distance.
+    pub fn candidate_pairs(&self, reach: f64) -> Vec<(usize, usize)> {
+        // Look far enough out that no pair within `reach` is missed, whatever
+        // the cell size.
+        let span = (reach / self.cell_size).ceil().max(1.0) as i32;
+        let mut pairs = Vec::new();
+        for (cell, indices) in self.cells.iter() {
+            for x in -span..=span {
+                for y in -span..=span {
+                    for z in -span..=span {
+                        let Some(neighbors) = self.cells.get(&(*cell + IVec3::new(x, y, z))) else {
+                            continue;
+                        };
+                        for &i in indices {
+                            pairs.extend(neighbors.iter().filter(|&&j| i < j).map(|&j| (i, j)));
+                        }
+                    }
+                }
+            }
+        }
+        pairs
+    }
+}
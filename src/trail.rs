@@ -0,0 +1,640 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::utils::HashSet;
+use crate::input::{action_just_pressed, Action, KeyBindings};
+use crate::simulation::{
+    run_physics_substeps, Body, BodyColor, BodyConfig, Config, OrphanedTrails, Position, RenderOrigin,
+    SimClock, SimulationState, TrailBlend, TrailColoring, TrailJoin, TrailSpacing, Velocity,
+};
+
+/// Depth offset per trail sample of age, see `Trail::mesh_positions`. Small
+/// enough that even long trails stay within a fraction of a body radius.
+const TRAIL_DEPTH_STEP: f32 = 1e-4;
+
+/// Longest a `TrailJoin::Miter` tip may reach from its point, in half
+/// widths, before the join falls back to a bevel.
+const MITER_LIMIT: f32 = 4.;
+
+/// Widest angle one triangle of a `TrailJoin::Round` spans.
+const ROUND_JOIN_STEP: f32 = std::f32::consts::PI / 8.;
+
+/// Ribbon points closer than this many half widths to the previous point
+/// are merged into it, see `ribbon`.
+const RIBBON_MERGE_DISTANCE: f32 = 1e-3;
+
+/// Whether trails follow their bodies, stay put as a static reference
+/// while the bodies move on, or are hidden. Leaving `Freeze` or `Hide`
+/// restarts every trail at its body, so no segment jumps the gap.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default, States)]
+pub enum TrailState {
+    Hide,
+    #[default]
+    Show,
+    Freeze,
+}
+
+#[derive(Bundle)]
+pub(crate) struct TrailBundle {
+    trail: Trail,
+    decay: TrailDecay,
+    color: TrailColor,
+    mesh: PbrBundle,
+}
+
+#[derive(Component, Default)]
+pub(crate) struct Trail {
+    max_length: usize,
+    min_spacing: Option<f32>,
+    spacing_mode: TrailSpacing,
+    coloring: TrailColoring,
+    width: Option<f32>,
+    join: TrailJoin,
+    pub(crate) points: Vec<Vec3>,
+    /// Body speed when each point was recorded, parallel to `points`.
+    pub(crate) speeds: Vec<f32>,
+}
+
+impl Trail {
+    /// Appends a point, dropping the oldest one past `max_length`. With a
+    /// minimum spacing the newest point follows the body until it is far
+    /// enough from the one before it, so the trail stays attached.
+    fn record(&mut self, point: Vec3, speed: f32) {
+        if let TrailSpacing::ArcLength(spacing) = self.spacing_mode {
+            if spacing > 0. {
+                self.record_arc_length(point, speed, spacing);
+                return;
+            }
+        }
+        if let Some(spacing) = self.min_spacing {
+            let len = self.points.len();
+            if len >= 2 && self.points[len - 2].distance(point) < spacing {
+                self.points[len - 1] = point;
+                self.speeds[len - 1] = speed;
+                return;
+            }
+        }
+        self.push(point, speed);
+    }
+
+    /// `TrailSpacing::ArcLength`: every point but the newest sits `spacing`
+    /// after the one before it; the newest follows the body.
+    fn record_arc_length(&mut self, point: Vec3, speed: f32, spacing: f32) {
+        if self.points.len() >= 2 {
+            self.points.pop();
+            self.speeds.pop();
+        }
+        let Some(mut last) = self.points.last().copied() else {
+            self.push(point, speed);
+            return;
+        };
+        // A fast body can lay down many points in one tick; they're trimmed
+        // to `max_length` once, not one shift of the whole trail per point.
+        while last.distance(point) >= spacing {
+            last += (point - last).normalize() * spacing;
+            self.points.push(last);
+            self.speeds.push(speed);
+        }
+        self.push(point, speed);
+    }
+
+    /// Appends a point, then drops the oldest ones past `max_length`.
+    fn push(&mut self, point: Vec3, speed: f32) {
+        self.points.push(point);
+        self.speeds.push(speed);
+        let excess = self.points.len().saturating_sub(self.max_length);
+        self.points.drain(..excess);
+        self.speeds.drain(..excess);
+    }
+
+    /// Moves every point by `offset`, after the render origin moved by `-offset`.
+    pub(crate) fn shift(&mut self, offset: Vec3) {
+        for point in &mut self.points {
+            *point += offset;
+        }
+    }
+
+    /// Drops the history and restarts the trail at `point`.
+    pub(crate) fn restart(&mut self, point: Vec3) {
+        let speed = self.speeds.last().copied().unwrap_or_default();
+        self.points = vec![point];
+        self.speeds = vec![speed];
+    }
+
+    /// Points as uploaded to the mesh: each one pushed back along the view
+    /// axis (the camera looks down -Z) by `TRAIL_DEPTH_STEP` per sample of
+    /// age. Trails are sampled on the same physics ticks, so under the depth
+    /// test newer segments draw over older ones across trails (exactly so
+    /// with neither `trail_min_spacing` nor arc-length spacing), and the head
+    /// stays level with its body.
+    /// Materials keep their default opaque mode with depth writes: ordering
+    /// comes from the depth test, not blending, and the glow is unchanged.
+    pub(crate) fn mesh_positions(&self) -> Vec<[f32; 3]> {
+        let newest = self.points.len().saturating_sub(1);
+        self.points.iter()
+            .enumerate()
+            .map(|(i, p)| (*p - Vec3::Z * (newest - i) as f32 * TRAIL_DEPTH_STEP).to_array())
+            .collect()
+    }
+
+    /// Uploads the trail to its mesh: a line strip through `mesh_positions`,
+    /// or with a width a ribbon along them as a triangle list.
+    pub(crate) fn write_mesh(&self, mesh: &mut Mesh) {
+        let positions = self.mesh_positions();
+        let colors = self.vertex_colors();
+        let Some(width) = self.width else {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+            if let Some(colors) = colors {
+                mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+            }
+            return;
+        };
+        let ribbon = ribbon(&positions, colors.as_deref(), width / 2., self.join);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, ribbon.positions);
+        if let Some(colors) = ribbon.colors {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+        mesh.insert_indices(Indices::U32(ribbon.indices));
+    }
+
+    /// Per-vertex colors for `TrailColoring::Speed`, `None` for flat trails.
+    pub(crate) fn vertex_colors(&self) -> Option<Vec<[f32; 4]>> {
+        match self.coloring {
+            TrailColoring::Flat => None,
+            TrailColoring::Speed { .. } => Some(
+                self.speeds.iter()
+                    .map(|speed| self.coloring.color_at_speed(*speed as f64).to_f32_array())
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Triangles for a ribbon trail, see `ribbon`.
+struct Ribbon {
+    positions: Vec<[f32; 3]>,
+    colors: Option<Vec<[f32; 4]>>,
+    indices: Vec<u32>,
+}
+
+impl Ribbon {
+    /// Adds a vertex at `at` (in the XY plane, at depth `z`) colored like
+    /// source point `source`, returning its index.
+    fn vertex(&mut self, at: Vec2, z: f32, source: [f32; 4]) -> u32 {
+        self.positions.push([at.x, at.y, z]);
+        if let Some(colors) = &mut self.colors {
+            colors.push(source);
+        }
+        self.positions.len() as u32 - 1
+    }
+}
+
+/// Builds a flat ribbon `2 * half_width` wide along `points` in the XY view
+/// plane, with `join` filling the outer corner wherever consecutive segments
+/// turn. Each segment is its own quad, so the inner side of a turn simply
+/// overlaps.
+///
+/// Points within `RIBBON_MERGE_DISTANCE` half widths of the previous kept
+/// point have no usable direction (a coincident pair would make a NaN
+/// normal), so they are merged into it; the newer one wins, keeping the
+/// head on the body. With fewer than two distinct points the ribbon is a
+/// single zero-area triangle at the point, which draws nothing but keeps
+/// the mesh non-empty.
+fn ribbon(points: &[[f32; 3]], colors: Option<&[[f32; 4]]>, half_width: f32, join: TrailJoin) -> Ribbon {
+    let mut kept: Vec<usize> = vec![];
+    for (i, point) in points.iter().enumerate() {
+        match kept.last() {
+            Some(&last) if Vec2::from_slice(&points[last]).distance(Vec2::from_slice(point))
+                < half_width * RIBBON_MERGE_DISTANCE =>
+            {
+                *kept.last_mut().unwrap() = i;
+            }
+            _ => kept.push(i),
+        }
+    }
+    let mut ribbon = Ribbon {
+        positions: vec![],
+        colors: colors.map(|_| vec![]),
+        indices: vec![],
+    };
+    let at = |k: usize| Vec2::from_slice(&points[kept[k]]);
+    let z = |k: usize| points[kept[k]][2];
+    let color = |k: usize| colors.map_or([1.; 4], |colors| colors[kept[k]]);
+
+    if kept.len() < 2 {
+        if let Some(&only) = kept.first() {
+            let point = Vec2::from_slice(&points[only]);
+            let source = colors.map_or([1.; 4], |colors| colors[only]);
+            for _ in 0..3 {
+                let index = ribbon.vertex(point, points[only][2], source);
+                ribbon.indices.push(index);
+            }
+        }
+        return ribbon;
+    }
+
+    let directions: Vec<Vec2> = (0..kept.len() - 1)
+        .map(|k| (at(k + 1) - at(k)).normalize())
+        .collect();
+    let normal = |direction: Vec2| direction.perp() * half_width;
+
+    for (k, direction) in directions.iter().enumerate() {
+        let offset = normal(*direction);
+        let a_left = ribbon.vertex(at(k) + offset, z(k), color(k));
+        let a_right = ribbon.vertex(at(k) - offset, z(k), color(k));
+        let b_left = ribbon.vertex(at(k + 1) + offset, z(k + 1), color(k + 1));
+        let b_right = ribbon.vertex(at(k + 1) - offset, z(k + 1), color(k + 1));
+        ribbon.indices.extend([a_left, a_right, b_left, b_left, a_right, b_right]);
+    }
+
+    for k in 1..kept.len() - 1 {
+        let (before, after) = (directions[k - 1], directions[k]);
+        let turn = before.perp_dot(after);
+        if turn.abs() < 1e-6 && before.dot(after) > 0. {
+            continue;
+        }
+        // The gap opens on the outside of the turn: right of a left turn.
+        let side = if turn > 0. { -1. } else { 1. };
+        let (from, to) = (normal(before) * side, normal(after) * side);
+        let (center, depth, source) = (at(k), z(k), color(k));
+        let hub = ribbon.vertex(center, depth, source);
+        let fan = |ribbon: &mut Ribbon, edges: &[Vec2]| {
+            let rim: Vec<u32> = edges.iter().map(|edge| ribbon.vertex(center + *edge, depth, source)).collect();
+            for pair in rim.windows(2) {
+                ribbon.indices.extend([hub, pair[0], pair[1]]);
+            }
+        };
+        match join {
+            TrailJoin::Bevel => fan(&mut ribbon, &[from, to]),
+            TrailJoin::Miter => {
+                let bisector = (from + to).normalize_or_zero();
+                // The tip lies `half_width / cos` from the point.
+                let cos = bisector.dot(to) / half_width;
+                if cos >= 1. / MITER_LIMIT {
+                    fan(&mut ribbon, &[from, bisector * half_width / cos, to]);
+                } else {
+                    fan(&mut ribbon, &[from, to]);
+                }
+            }
+            TrailJoin::Round => {
+                let angle = from.angle_between(to);
+                let steps = (angle.abs() / ROUND_JOIN_STEP).ceil().max(1.) as usize;
+                let edges: Vec<Vec2> = (0..=steps)
+                    .map(|step| Vec2::from_angle(angle * step as f32 / steps as f32).rotate(from))
+                    .collect();
+                fan(&mut ribbon, &edges);
+            }
+        }
+    }
+    ribbon
+}
+
+#[allow(dead_code)]
+#[derive(Component)]
+struct TrailDecay(Duration);
+
+#[derive(Component)]
+pub(crate) struct TrailColor(pub(crate) Color);
+
+#[derive(Component)]
+pub(crate) struct TrailRef(pub(crate) Entity);
+
+/// The body a trail belongs to, the reverse of `TrailRef`. Still readable
+/// once the body is gone, so `despawn_orphaned_trails` can find the trail.
+#[derive(Component)]
+pub(crate) struct TrailOwner(pub(crate) Entity);
+
+/// Records and draws a trail behind every body.
+///
+/// Added by `GravityPlugin` unless it was built with `trails(false)`, so
+/// hosts with their own visualization can leave it out: it only reads the
+/// public body components and `RenderOrigin`, and the physics runs the same
+/// without it. By default it initializes `TrailState`; opt out with
+/// `manage_state(false)` and initialize it in the host instead.
+pub struct TrailPlugin {
+    manage_state: bool,
+}
+
+impl Default for TrailPlugin {
+    fn default() -> Self {
+        Self { manage_state: true }
+    }
+}
+
+impl TrailPlugin {
+    /// Whether the plugin initializes `TrailState`.
+    pub fn manage_state(mut self, manage: bool) -> Self {
+        self.manage_state = manage;
+        self
+    }
+}
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        if self.manage_state {
+            app.init_state::<TrailState>();
+        }
+        app.init_resource::<KeyBindings>()
+            .init_resource::<RenderOrigin>()
+            .add_systems(
+                FixedUpdate,
+                update_trail
+                    .after(run_physics_substeps)
+                    .run_if(in_state(SimulationState::Running))
+                    .run_if(in_state(TrailState::Show)),
+            )
+            .add_systems(
+                Update,
+                (
+                    draw_trail
+                        .run_if(not(in_state(TrailState::Hide)))
+                        .run_if(in_state(SimulationState::Running)),
+                    toggle_trail.run_if(action_just_pressed(Action::ToggleTrails)),
+                    sync_trail_color,
+                    despawn_orphaned_trails,
+                    fade_out_trails,
+                ),
+            )
+            .add_systems(OnEnter(TrailState::Show), restart_trails)
+            .add_systems(OnEnter(TrailState::Hide), set_trail_visibility(Visibility::Hidden))
+            .add_systems(OnExit(TrailState::Hide), set_trail_visibility(Visibility::Inherited));
+    }
+}
+
+/// Records a point on every trail, every `Config::trail_interval` ticks
+/// for time-spaced trails and every tick for arc-length ones.
+pub(crate) fn update_trail(
+    mut query: Query<(&Position, &Velocity, &TrailRef), With<Body>>,
+    mut trail_query: Query<&mut Trail, With<Trail>>,
+    origin: Res<RenderOrigin>,
+    clock: Res<SimClock>,
+    config: Res<Config>,
+) {
+    let interval_due = clock.steps % u64::from(config.trail_interval.max(1)) == 0;
+    for (pos, vel, trail_entity) in query.iter_mut() {
+        if let Ok(mut trail) = trail_query.get_mut(trail_entity.0) {
+            if interval_due || matches!(trail.spacing_mode, TrailSpacing::ArcLength(_)) {
+                trail.record(origin.to_render(pos.0), vel.0.length() as f32);
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn draw_trail(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut query: Query<(Entity, &BodyConfig, &BodyColor, &GlobalTransform, Option<&TrailRef>), With<Body>>,
+    mut trail_entity_query: Query<(&Trail, &Handle<Mesh>, &Handle<StandardMaterial>), With<Trail>>,
+    origin: Res<RenderOrigin>,
+    sim_config: Res<Config>,
+) {
+    for (
+        body, config, body_color, transform, trail
+    ) in query.iter_mut() {
+        if let Some(trail_entity) = trail {
+            if let Ok(
+                (trail, trail_mesh_handle, _trail_material_handle)
+            ) = trail_entity_query.get_mut(trail_entity.0) {
+                trail.write_mesh(meshes.get_mut(trail_mesh_handle).unwrap());
+                continue;
+            } else {
+                commands.entity(trail_entity.0).despawn_recursive();
+            }
+        }
+        let trail = commands.spawn((
+            trail_bundle(
+                config,
+                body_color.0,
+                vec![origin.to_render(config.position)],
+                vec![config.velocity.length() as f32],
+                *transform,
+                sim_config.trail_blend,
+                &mut meshes,
+                &mut materials,
+            ),
+            TrailOwner(body),
+        )).id();
+
+        commands.get_entity(body).unwrap().insert(TrailRef(trail));
+    }
+}
+
+/// A trail entity for the body described by `config`, through render-space
+/// `points` recorded at `speeds`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn trail_bundle(
+    config: &BodyConfig,
+    body_color: LinearRgba,
+    points: Vec<Vec3>,
+    speeds: Vec<f32>,
+    transform: GlobalTransform,
+    blend: TrailBlend,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) -> TrailBundle {
+    let trail_color = config.trail_color.unwrap_or(body_color);
+    let trail_material_handle = materials.add(trail_material(config.trail_coloring, trail_color, blend));
+
+    // Create the trail mesh
+    let mut trail_mesh = Mesh::new(
+        match config.trail_width {
+            Some(_) => PrimitiveTopology::TriangleList,
+            None => PrimitiveTopology::LineStrip,
+        },
+        RenderAssetUsages::default()
+    );
+    let trail_data = Trail {
+        max_length: config.trail_length,
+        min_spacing: config.trail_min_spacing,
+        spacing_mode: config.trail_spacing_mode,
+        coloring: config.trail_coloring,
+        width: config.trail_width,
+        join: config.trail_join,
+        points,
+        speeds,
+    };
+    trail_data.write_mesh(&mut trail_mesh);
+    let trail_mesh_handle = meshes.add(trail_mesh);
+
+    TrailBundle {
+        trail: trail_data,
+        decay: TrailDecay(Duration::new(1, 0)),
+        color: TrailColor(trail_color.into()),
+        mesh: PbrBundle {
+            mesh: trail_mesh_handle,
+            material: trail_material_handle,
+            global_transform: transform,
+            ..default()
+        }
+    }
+}
+
+fn trail_material(coloring: TrailColoring, color: LinearRgba, blend: TrailBlend) -> StandardMaterial {
+    let material = match coloring {
+        TrailColoring::Flat => StandardMaterial {
+            // Alpha comes from the base color; emission alone has none.
+            base_color: Color::WHITE.with_alpha(color.alpha),
+            emissive: color,
+            // Ribbon triangles wind either way depending on the turn.
+            cull_mode: None,
+            ..default()
+        },
+        // Vertex colors only tint the base color, so render it unlit
+        // and let HDR vertex colors drive the glow.
+        TrailColoring::Speed { .. } => StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            cull_mode: None,
+            ..default()
+        },
+    };
+    StandardMaterial {
+        alpha_mode: blend.alpha_mode(),
+        ..material
+    }
+}
+
+/// Recolors a flat trail when its body's color or `trail_color` changes.
+/// Speed-colored trails take their colors from the ramp and are left alone.
+#[allow(clippy::type_complexity)]
+fn sync_trail_color(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    bodies: Query<(&BodyConfig, &BodyColor, &TrailRef), (With<Body>, Or<(Changed<BodyColor>, Changed<BodyConfig>)>)>,
+    mut trails: Query<(&mut TrailColor, &Handle<StandardMaterial>)>,
+) {
+    for (config, body_color, trail) in bodies.iter() {
+        let Ok((mut trail_color, handle)) = trails.get_mut(trail.0) else {
+            continue;
+        };
+        let color = config.trail_color.unwrap_or(body_color.0);
+        trail_color.0 = color.into();
+        if let (TrailColoring::Flat, Some(material)) = (config.trail_coloring, materials.get_mut(handle)) {
+            material.base_color.set_alpha(color.alpha);
+            material.emissive = color;
+        }
+    }
+}
+
+/// A trail whose body is gone, fading out under `OrphanedTrails::FadeOut`.
+#[derive(Component)]
+pub(crate) struct FadingOut {
+    duration: f32,
+    elapsed: f32,
+    /// Alpha of `TrailColor` when the fade began.
+    from_alpha: f32,
+}
+
+/// Applies `Config::orphaned_trails` to the trail of every body that lost
+/// its `Body` since the last run, rather than leaving it to `draw_trail`'s
+/// lazy check.
+#[allow(clippy::type_complexity)]
+fn despawn_orphaned_trails(
+    mut commands: Commands,
+    mut removed: RemovedComponents<Body>,
+    config: Res<Config>,
+    trails: Query<(Entity, &TrailOwner, &TrailColor, &Handle<Mesh>, &Handle<StandardMaterial>), Without<FadingOut>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let removed: HashSet<Entity> = removed.read().collect();
+    if removed.is_empty() {
+        return;
+    }
+    for (trail, owner, color, mesh, material) in trails.iter() {
+        if !removed.contains(&owner.0) {
+            continue;
+        }
+        match config.orphaned_trails {
+            OrphanedTrails::Keep => {}
+            OrphanedTrails::FadeOut(duration) if duration > 0. => {
+                // An opaque material ignores alpha, so blend it while it fades.
+                if let Some(material) = materials.get_mut(material) {
+                    if material.alpha_mode == AlphaMode::Opaque {
+                        material.alpha_mode = AlphaMode::Blend;
+                    }
+                }
+                commands.entity(trail).insert(FadingOut {
+                    duration,
+                    elapsed: 0.,
+                    from_alpha: color.0.alpha(),
+                });
+            }
+            // A zero or NaN fade has nothing to ramp over, or for
+            // `fade_out_trails` to divide by.
+            OrphanedTrails::Despawn | OrphanedTrails::FadeOut(_) => {
+                despawn_trail(&mut commands, &mut meshes, &mut materials, trail, mesh, material);
+            }
+        }
+    }
+}
+
+/// Ramps fading trails' `TrailColor` and material alpha down to zero, then
+/// despawns them.
+#[allow(clippy::type_complexity)]
+fn fade_out_trails(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut trails: Query<(Entity, &mut FadingOut, &mut TrailColor, &Handle<Mesh>, &Handle<StandardMaterial>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (trail, mut fade, mut color, mesh, material) in trails.iter_mut() {
+        fade.elapsed += time.delta_seconds();
+        if fade.elapsed >= fade.duration {
+            despawn_trail(&mut commands, &mut meshes, &mut materials, trail, mesh, material);
+            continue;
+        }
+        let alpha = fade.from_alpha * (1. - fade.elapsed / fade.duration);
+        color.0.set_alpha(alpha);
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color.set_alpha(alpha);
+        }
+    }
+}
+
+fn despawn_trail(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    trail: Entity,
+    mesh: &Handle<Mesh>,
+    material: &Handle<StandardMaterial>,
+) {
+    meshes.remove(mesh);
+    materials.remove(material);
+    commands.entity(trail).despawn_recursive();
+}
+
+fn toggle_trail(
+    state: Res<State<TrailState>>,
+    mut next_state: ResMut<NextState<TrailState>>,
+) {
+    match state.get() {
+        TrailState::Show => next_state.set(TrailState::Freeze),
+        TrailState::Freeze => next_state.set(TrailState::Hide),
+        TrailState::Hide => next_state.set(TrailState::Show),
+    }
+}
+
+fn restart_trails(
+    bodies: Query<(&Position, &TrailRef), With<Body>>,
+    mut trails: Query<&mut Trail>,
+    origin: Res<RenderOrigin>,
+) {
+    for (position, trail) in bodies.iter() {
+        if let Ok(mut trail) = trails.get_mut(trail.0) {
+            trail.restart(origin.to_render(position.0));
+        }
+    }
+}
+
+fn set_trail_visibility(visibility: Visibility) -> impl FnMut(Query<&mut Visibility, With<Trail>>) {
+    move |mut query| {
+        for mut trail in query.iter_mut() {
+            *trail = visibility;
+        }
+    }
+}
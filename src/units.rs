@@ -0,0 +1,140 @@
+use bevy::math::DVec3;
+use serde::{Deserialize, Serialize};
+use crate::simulation::{BodyConfig, Config};
+
+/// Gaussian gravitational constant `k`, in AU^(3/2) / (day · solar mass^(1/2)).
+const GAUSSIAN_GRAVITATIONAL_CONSTANT: f64 = 0.01720209895;
+
+const SOLAR_MASS_KG: f64 = 1.98847e30;
+const AU_M: f64 = 1.495978707e11;
+const DAY_S: f64 = 86_400.;
+const YEAR_S: f64 = 365.25 * DAY_S;
+/// A twelfth of a 365-day year, as the demo's "2 months per second" counts it.
+const MONTH_S: f64 = 365. * DAY_S / 12.;
+
+/// Units that positions, velocities, masses and `Config::timestep` are
+/// measured in. Picking one sets the gravitational constant; the helpers
+/// convert familiar quantities into the system's base units.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum UnitSystem {
+    /// The original arbitrary units of the demo scenarios.
+    #[default]
+    Simulation,
+    /// Meters, kilograms and seconds.
+    #[serde(rename = "SI")]
+    Si,
+    /// Astronomical units, solar masses and days.
+    AstronomicalAuDay,
+    /// `G = 1`, with AU and solar masses: one time unit is a year / 2π, so
+    /// a 1 AU orbit around one solar mass has unit speed.
+    Natural,
+}
+
+impl UnitSystem {
+    pub fn gravitational_constant(self) -> f64 {
+        match self {
+            UnitSystem::Simulation => 11.334e-12,
+            UnitSystem::Si => 6.6743e-11,
+            UnitSystem::AstronomicalAuDay => GAUSSIAN_GRAVITATIONAL_CONSTANT.powi(2),
+            UnitSystem::Natural => 1.,
+        }
+    }
+
+    /// Length of one base time unit in seconds, `None` for `Simulation`.
+    fn seconds_per_time_unit(self) -> Option<f64> {
+        match self {
+            UnitSystem::Simulation => None,
+            UnitSystem::Si => Some(1.),
+            UnitSystem::AstronomicalAuDay => Some(DAY_S),
+            UnitSystem::Natural => Some(YEAR_S / std::f64::consts::TAU),
+        }
+    }
+
+    /// Length of one base distance unit in meters, `None` for `Simulation`.
+    fn meters_per_distance_unit(self) -> Option<f64> {
+        match self {
+            UnitSystem::Simulation => None,
+            UnitSystem::Si => Some(1.),
+            UnitSystem::AstronomicalAuDay | UnitSystem::Natural => Some(AU_M),
+        }
+    }
+
+    /// Mass of one base mass unit in kilograms, `None` for `Simulation`.
+    fn kilograms_per_mass_unit(self) -> Option<f64> {
+        match self {
+            UnitSystem::Simulation => None,
+            UnitSystem::Si => Some(1.),
+            UnitSystem::AstronomicalAuDay | UnitSystem::Natural => Some(SOLAR_MASS_KG),
+        }
+    }
+
+    /// A mass given in solar masses. `Simulation` units have no physical
+    /// scale, so values pass through unchanged there.
+    pub fn solar_masses(self, solar_masses: f64) -> f64 {
+        self.kilograms_per_mass_unit()
+            .map_or(solar_masses, |kg| solar_masses * SOLAR_MASS_KG / kg)
+    }
+
+    /// A distance given in astronomical units.
+    pub fn au(self, au: f64) -> f64 {
+        self.meters_per_distance_unit().map_or(au, |m| au * AU_M / m)
+    }
+
+    /// A speed given in kilometers per second.
+    pub fn km_per_s(self, km_per_s: f64) -> f64 {
+        match (self.meters_per_distance_unit(), self.seconds_per_time_unit()) {
+            (Some(m), Some(s)) => km_per_s * 1000. * s / m,
+            _ => km_per_s,
+        }
+    }
+
+    /// A duration given in days, e.g. for `Config::timestep`.
+    pub fn days(self, days: f64) -> f64 {
+        self.seconds_per_time_unit().map_or(days, |s| days * DAY_S / s)
+    }
+
+    /// A duration given in seconds. `Simulation` time is counted in
+    /// seconds, as `Config::timestep` describes it.
+    pub fn seconds(self, seconds: f64) -> f64 {
+        self.seconds_per_time_unit().map_or(seconds, |s| seconds / s)
+    }
+}
+
+impl Config {
+    /// Sets `timestep` so `simulated_seconds` pass per real second, in
+    /// whatever `units` the config already has.
+    pub fn with_time_scale(self, simulated_seconds: f64) -> Self {
+        Self {
+            timestep: self.units.seconds(simulated_seconds),
+            ..self
+        }
+    }
+
+    /// `with_time_scale` in days of simulated time per real second.
+    pub fn days_per_second(self, days: f64) -> Self {
+        self.with_time_scale(days * DAY_S)
+    }
+
+    /// `with_time_scale` in months, each a twelfth of a 365-day year.
+    pub fn months_per_second(self, months: f64) -> Self {
+        self.with_time_scale(months * MONTH_S)
+    }
+
+    /// `with_time_scale` in Julian years of 365.25 days.
+    pub fn years_per_second(self, years: f64) -> Self {
+        self.with_time_scale(years * YEAR_S)
+    }
+}
+
+impl BodyConfig {
+    /// A body with mass in solar masses, position in AU and velocity in
+    /// km/s, converted into `units`. Everything else is the default.
+    pub fn astronomical(units: UnitSystem, solar_masses: f64, position_au: DVec3, velocity_km_s: DVec3) -> Self {
+        Self {
+            mass: units.solar_masses(solar_masses),
+            position: position_au.to_array().map(|c| units.au(c)).into(),
+            velocity: velocity_km_s.to_array().map(|c| units.km_per_s(c)).into(),
+            ..Self::default()
+        }
+    }
+}
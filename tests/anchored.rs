@@ -0,0 +1,19 @@
+mod common;
+
+use bevy::math::DVec3;
+use three_body::simulation::{step, BodyConfig};
+
+#[test]
+fn an_anchored_star_stays_put_while_a_planet_orbits() {
+    let star = BodyConfig { anchored: true, ..common::body(1., DVec3::new(0.5, -0.25, 0.), DVec3::ZERO) };
+    let planet = common::body(0.1, DVec3::new(1.5, -0.25, 0.), DVec3::new(0., 1., 0.));
+    let mut world = common::world(common::natural(vec![star, planet]));
+    let start = common::bodies(&mut world);
+
+    for _ in 0..20 {
+        step(&mut world, 50);
+        let bodies = common::bodies(&mut world);
+        assert_eq!(bodies[0], start[0], "the star moved");
+        assert_ne!(bodies[1].1, start[1].1, "the planet should move");
+    }
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use three_body::diagnostics::AngularMomentum;
+use three_body::simulation::{step, Config};
+
+/// Largest change in `L` allowed, relative to `Σ m |r| |v|`, the largest
+/// `|L|` the bodies' speeds could add up to. Central pair forces under
+/// semi-implicit Euler keep `L` exactly, so what's left is rounding.
+const TOLERANCE: f64 = 1e-9;
+
+fn assert_conserved(config: Config) {
+    let mut world = common::world(config);
+    let initial = AngularMomentum::from_states(&common::states(&mut world)).0;
+    for _ in 0..20 {
+        step(&mut world, 100);
+        let states = common::states(&mut world);
+        let scale: f64 = states.iter().map(|(m, r, v)| m * r.length() * v.length()).sum();
+        let change = (AngularMomentum::from_states(&states).0 - initial).length() / scale;
+        assert!(change < TOLERANCE, "L changed by {change:e} of its scale");
+    }
+}
+
+#[test]
+fn angular_momentum_is_conserved_in_the_demo() {
+    // Let go at rest, so `L` starts at zero and has to stay there.
+    assert_conserved(Config::demo());
+}
+
+#[test]
+fn angular_momentum_is_conserved_on_a_tumbling_orbit() {
+    assert_conserved(common::three_bodies());
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use three_body::simulation::{BodyConfig, TrailSpacing};
+
+const SPACING: f32 = 0.02;
+
+/// Points of the longest trail, oldest first, read back from its mesh.
+fn longest_trail(app: &mut App) -> Vec<Vec3> {
+    common::trails(app)
+        .into_iter()
+        .map(|trail| {
+            let handle = app.world().get::<Handle<Mesh>>(trail).unwrap();
+            let mesh = app.world().resource::<Assets<Mesh>>().get(handle).unwrap();
+            let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION);
+            let Some(VertexAttributeValues::Float32x3(points)) = positions else {
+                panic!("line trails should have 3D positions");
+            };
+            points.iter().map(|point| Vec3::from_array(*point)).collect::<Vec<_>>()
+        })
+        .max_by_key(Vec::len)
+        .unwrap()
+}
+
+#[test]
+fn arc_length_trails_are_evenly_spaced() {
+    // Fast at periapsis and slow at apoapsis, so time-spaced points would
+    // bunch up on one side.
+    let star = BodyConfig { anchored: true, ..common::body(1., DVec3::ZERO, DVec3::ZERO) };
+    let planet = BodyConfig {
+        trail_spacing_mode: TrailSpacing::ArcLength(SPACING),
+        trail_length: 5000,
+        ..common::body(1e-6, DVec3::new(0.5, 0., 0.), DVec3::new(0., 1.9, 0.))
+    };
+    let mut app = common::app(common::natural(vec![star, planet]));
+    for _ in 0..400 {
+        app.update();
+    }
+    let points = longest_trail(&mut app);
+    assert!(points.len() > 50, "only {} points", points.len());
+    // The newest point follows the body, so its gap is shorter.
+    for pair in points[..points.len() - 1].windows(2) {
+        let gap = pair[0].truncate().distance(pair[1].truncate());
+        assert!((gap - SPACING).abs() < 1e-3 * SPACING, "points {gap} apart");
+    }
+}
+
+#[test]
+fn many_points_per_tick_stay_capped_at_trail_length() {
+    // About fifteen points a tick, so each tick overflows the cap many times.
+    let spacing = 0.001;
+    let drifter = BodyConfig {
+        trail_spacing_mode: TrailSpacing::ArcLength(spacing),
+        trail_length: 40,
+        ..common::body(0., DVec3::ZERO, DVec3::X)
+    };
+    let mut app = common::app(common::natural(vec![drifter]));
+    for _ in 0..20 {
+        app.update();
+    }
+    let points = longest_trail(&mut app);
+    assert_eq!(points.len(), 40);
+    // The kept points are the newest, still evenly spaced behind the body.
+    for pair in points[..points.len() - 1].windows(2) {
+        let gap = pair[0].truncate().distance(pair[1].truncate());
+        assert!((gap - spacing).abs() < 1e-2 * spacing, "points {gap} apart");
+    }
+    let body = common::bodies(app.world_mut())[0].1.as_vec3().truncate();
+    assert!(points[points.len() - 1].truncate().distance(body) < 1e-4);
+}
@@ -0,0 +1,43 @@
+use bevy::math::DVec3;
+use three_body::scenario::ScenarioError;
+use three_body::simulation::Config;
+
+#[test]
+fn table_skips_comments_and_blank_lines() {
+    let table = "\
+# mass x y z vx vy vz
+1.0  0 0 0   0 0 0
+
+   # an indented comment
+0.001 1 2 3\t0.5 -0.5 0
+";
+    let config = Config::from_ascii_table(table.as_bytes()).unwrap();
+    let bodies: Vec<_> = config.initial_bodies.iter()
+        .map(|body| (body.mass, body.position, body.velocity))
+        .collect();
+    assert_eq!(bodies, [
+        (1., DVec3::ZERO, DVec3::ZERO),
+        (0.001, DVec3::new(1., 2., 3.), DVec3::new(0.5, -0.5, 0.)),
+    ]);
+}
+
+#[test]
+fn malformed_rows_report_their_line() {
+    let short = "# header\n1 0 0 0 0 0 0\n1 2 3\n";
+    match Config::from_ascii_table(short.as_bytes()) {
+        Err(ScenarioError::Table { line, message }) => {
+            assert_eq!(line, 3);
+            assert!(message.contains("expected 7 columns"), "{message}");
+        }
+        other => panic!("expected a table error, got {:?}", other.map(|config| config.initial_bodies.len())),
+    }
+
+    let unparsable = "1 0 0 0 0 0 0\n\n1 0 0 zero 0 0 0\n";
+    match Config::from_ascii_table(unparsable.as_bytes()) {
+        Err(ScenarioError::Table { line, message }) => {
+            assert_eq!(line, 3);
+            assert!(message.contains("\"zero\""), "{message}");
+        }
+        other => panic!("expected a table error, got {:?}", other.map(|config| config.initial_bodies.len())),
+    }
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use bevy::color::LinearRgba;
+use three_body::simulation::{BodyColor, BodyId, Config};
+
+/// Colors of three uncolored bodies spawned under `auto_palette`.
+fn colors(auto_palette: Option<Vec<LinearRgba>>) -> Vec<LinearRgba> {
+    let config = Config { auto_palette, ..common::three_bodies() };
+    assert!(config.initial_bodies.iter().all(|body| body.color.is_none() && body.group.is_none()));
+    let mut world = common::world(config);
+    let mut colors: Vec<_> = world.query::<(&BodyId, &BodyColor)>()
+        .iter(&world)
+        .map(|(id, color)| (*id, color.0))
+        .collect();
+    colors.sort_by_key(|(id, _)| *id);
+    colors.into_iter().map(|(_, color)| color).collect()
+}
+
+fn assert_distinct(colors: &[LinearRgba]) {
+    assert_eq!(colors.len(), 3);
+    for (i, a) in colors.iter().enumerate() {
+        for b in &colors[i + 1..] {
+            assert_ne!(a, b, "two bodies got the same color: {colors:?}");
+        }
+    }
+}
+
+#[test]
+fn golden_angle_walk_gives_distinct_colors() {
+    assert_distinct(&colors(Some(Vec::new())));
+}
+
+#[test]
+fn palette_is_used_in_spawn_order() {
+    let palette = vec![
+        LinearRgba::rgb(10., 0., 0.),
+        LinearRgba::rgb(0., 10., 0.),
+        LinearRgba::rgb(0., 0., 10.),
+    ];
+    let colors = colors(Some(palette.clone()));
+    assert_distinct(&colors);
+    assert_eq!(colors, palette);
+}
+
+#[test]
+fn no_palette_leaves_bodies_grey() {
+    let colors = colors(None);
+    assert!(colors.windows(2).all(|pair| pair[0] == pair[1]), "{colors:?}");
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use bevy::prelude::*;
+use three_body::cursor::CursorCoords;
+use three_body::selection::{Selected, SelectionPlugin};
+use three_body::simulation::{Body, BodyColor, BodyConfig, BodyId};
+
+/// `(config color, live color, material emissive)` of each body, by id.
+fn colors(app: &mut App) -> Vec<(Option<LinearRgba>, LinearRgba, LinearRgba)> {
+    let mut bodies: Vec<_> = app.world_mut()
+        .query_filtered::<(&BodyId, &BodyConfig, &BodyColor, &Handle<StandardMaterial>), With<Body>>()
+        .iter(app.world())
+        .map(|(id, config, color, material)| (*id, config.color, color.0, material.clone()))
+        .collect();
+    bodies.sort_by_key(|(id, ..)| *id);
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    bodies.into_iter()
+        .map(|(_, config, color, material)| (config, color, materials.get(&material).unwrap().emissive))
+        .collect()
+}
+
+#[test]
+fn recoloring_updates_the_config_and_the_material() {
+    let mut app = common::app(common::three_bodies());
+    app.init_resource::<CursorCoords>().add_plugins(SelectionPlugin);
+    app.update();
+    let before = colors(&mut app);
+    let selected = app.world_mut()
+        .query::<(Entity, &BodyId)>()
+        .iter(app.world())
+        .find(|(_, id)| id.0 == 1)
+        .unwrap()
+        .0;
+    app.world_mut().entity_mut(selected).insert(Selected);
+
+    common::tap(&mut app, KeyCode::KeyO);
+    let after = colors(&mut app);
+    let (config, color, emissive) = after[1];
+    assert_ne!(color, before[1].1, "the selected body should change color");
+    assert_eq!(config, Some(color));
+    assert_eq!(emissive, color);
+    assert_eq!(after[0], before[0]);
+    assert_eq!(after[2], before[2]);
+}
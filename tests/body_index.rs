@@ -0,0 +1,41 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{BodyId, BodyIndex};
+
+/// Every `(id, entity)` the index holds, checked against the bodies' own
+/// `BodyId`s.
+fn consistent_index(world: &mut World) -> Vec<(BodyId, Entity)> {
+    let indexed: Vec<_> = world.resource::<BodyIndex>().iter().collect();
+    let mut actual: Vec<_> = world
+        .query::<(&BodyId, Entity)>()
+        .iter(world)
+        .map(|(id, entity)| (*id, entity))
+        .collect();
+    actual.sort();
+    assert_eq!(indexed, actual, "the index should list exactly the live bodies");
+    assert_eq!(world.resource::<BodyIndex>().len(), actual.len());
+    indexed
+}
+
+fn ids(entries: &[(BodyId, Entity)]) -> Vec<BodyId> {
+    entries.iter().map(|(id, _)| *id).collect()
+}
+
+#[test]
+fn ids_are_sequential_and_survive_a_despawn() {
+    let mut world = common::world(common::three_bodies());
+    let initial = consistent_index(&mut world);
+    assert_eq!(ids(&initial), [BodyId(0), BodyId(1), BodyId(2)]);
+
+    world.despawn(initial[1].1);
+    assert_eq!(ids(&consistent_index(&mut world)), [BodyId(0), BodyId(2)]);
+    assert_eq!(world.resource::<BodyIndex>().get(BodyId(1)), None);
+
+    // The freed id isn't handed out again.
+    let spawned = common::spawn(&mut world, &common::body(1., DVec3::new(3., 0., 0.), DVec3::ZERO));
+    let after = consistent_index(&mut world);
+    assert_eq!(ids(&after), [BodyId(0), BodyId(2), BodyId(3)]);
+    assert_eq!(world.resource::<BodyIndex>().get(BodyId(3)), Some(spawned));
+}
@@ -0,0 +1,19 @@
+mod common;
+
+use bevy::prelude::*;
+use three_body::simulation::{Body, Config};
+
+#[test]
+fn an_invalid_icosphere_detail_falls_back_to_a_uv_sphere() {
+    let mut app = common::app(Config { body_mesh_detail: u8::MAX, ..common::three_bodies() });
+    app.update();
+    let world = app.world_mut();
+    let meshes: Vec<Handle<Mesh>> = world
+        .query_filtered::<&Handle<Mesh>, With<Body>>()
+        .iter(world)
+        .cloned()
+        .collect();
+    assert_eq!(meshes.len(), 3, "every body should get a mesh");
+    let sphere = world.resource::<Assets<Mesh>>().get(&meshes[0]).expect("the sphere should be loaded");
+    assert_eq!(sphere.count_vertices(), Sphere::new(1.).mesh().uv(32, 18).count_vertices());
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use bevy::ecs::world::Command;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{BodyConfig, BodySpawned, BodyId, Config, ResetSimulation};
+
+/// A world with no bodies that records `BodySpawned`, as the app does.
+fn world() -> World {
+    let mut world = World::new();
+    world.init_resource::<Events<BodySpawned>>();
+    ResetSimulation(common::natural(Vec::new())).apply(&mut world);
+    world
+}
+
+fn sent(world: &mut World) -> Vec<BodySpawned> {
+    world.resource_mut::<Events<BodySpawned>>().drain().collect()
+}
+
+#[test]
+fn a_spawn_sends_one_event_with_its_config() {
+    let mut world = world();
+    let body = BodyConfig { radius: 0.3, ..common::body(2., DVec3::new(1., 2., 3.), DVec3::new(0., -1., 0.)) };
+    let entity = common::spawn(&mut world, &body);
+
+    let events = sent(&mut world);
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.entity, entity);
+    assert_eq!(
+        (event.config.mass, event.config.radius, event.config.position, event.config.velocity),
+        (body.mass, body.radius, body.position, body.velocity),
+    );
+    // By the time anyone reads it, the entity is a full body.
+    assert!(world.get::<BodyId>(entity).is_some());
+}
+
+#[test]
+fn a_refused_spawn_sends_nothing() {
+    let mut world = world();
+    assert!(!world.resource::<Config>().allow_negative_mass);
+    common::spawn(&mut world, &common::body(-1., DVec3::ZERO, DVec3::ZERO));
+    assert!(sent(&mut world).is_empty());
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{BodyColor, BodyConfig, BodyId};
+
+/// Live color and material emissive of each body, by id.
+fn emissive(app: &mut App) -> Vec<(LinearRgba, LinearRgba)> {
+    let mut bodies: Vec<_> = app.world_mut()
+        .query::<(&BodyId, &BodyColor, &Handle<StandardMaterial>)>()
+        .iter(app.world())
+        .map(|(id, color, material)| (*id, color.0, material.clone()))
+        .collect();
+    bodies.sort_by_key(|(id, ..)| *id);
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    bodies.into_iter()
+        .map(|(_, color, material)| (color, materials.get(&material).unwrap().emissive))
+        .collect()
+}
+
+#[test]
+fn brightness_scales_the_normalized_color() {
+    let body = |x: f64, color: LinearRgba, brightness: Option<f32>| BodyConfig {
+        color: Some(color),
+        brightness,
+        ..common::body(1., DVec3::X * x, DVec3::ZERO)
+    };
+    let mut app = common::app(common::natural(vec![
+        body(0., LinearRgba::rgb(2., 1., 0.5), Some(10.)),
+        body(5., LinearRgba::rgb(200., 100., 50.), Some(10.)),
+        // Without a brightness the raw HDR color is the emissive.
+        body(10., LinearRgba::rgb(130., 50., 20.), None),
+    ]));
+    app.update();
+
+    let expected = [
+        LinearRgba::rgb(10., 5., 2.5),
+        LinearRgba::rgb(10., 5., 2.5),
+        LinearRgba::rgb(130., 50., 20.),
+    ];
+    for ((color, emissive), expected) in emissive(&mut app).into_iter().zip(expected) {
+        assert_eq!(color, expected);
+        assert_eq!(emissive, expected);
+    }
+}
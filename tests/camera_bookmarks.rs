@@ -0,0 +1,78 @@
+mod common;
+
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::ButtonState;
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use three_body::camera::{CameraBookmark, CameraPlugin};
+use three_body::cursor::MainCamera;
+use three_body::diagnostics::CenterOfMass;
+use three_body::simulation::{Config, RenderOrigin};
+
+fn app_with_camera(config: Config, translation: Vec3, scale: f32) -> App {
+    let mut app = common::app(config);
+    app.init_resource::<CenterOfMass>().add_plugins(CameraPlugin);
+    let projection = OrthographicProjection { scale, ..default() };
+    app.world_mut().spawn((
+        MainCamera,
+        Transform::from_translation(translation),
+        Projection::Orthographic(projection),
+    ));
+    app.update();
+    app
+}
+
+fn key(app: &mut App, key_code: KeyCode, state: ButtonState) {
+    app.world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+}
+
+/// Where the camera is centered, in world space, and its scale.
+fn view(app: &mut App) -> CameraBookmark {
+    let origin = *app.world().resource::<RenderOrigin>();
+    let (transform, projection) = app.world_mut()
+        .query_filtered::<(&Transform, &Projection), With<MainCamera>>()
+        .single(app.world());
+    let Projection::Orthographic(projection) = projection else {
+        panic!("the camera should stay orthographic");
+    };
+    CameraBookmark { center: origin.to_world(transform.translation).truncate(), scale: projection.scale }
+}
+
+#[test]
+fn bookmarks_survive_save_and_load() {
+    let mut config = common::three_bodies();
+    config.render.camera_transition = 0.;
+    let mut app = app_with_camera(config, Vec3::new(5., -3., 100.), 2.5);
+    key(&mut app, KeyCode::ControlLeft, ButtonState::Pressed);
+    let before = view(&mut app);
+    common::tap(&mut app, KeyCode::Digit3);
+    key(&mut app, KeyCode::ControlLeft, ButtonState::Released);
+    let stored = app.world().resource::<Config>().render.camera_bookmarks[&3];
+    // The floating origin moves each tick, and the camera in f32 with it.
+    assert!(stored.center.distance(before.center) < 1e-3, "stored {} at {}", stored.center, before.center);
+    assert_eq!(stored.scale, before.scale);
+
+    let path = std::env::temp_dir().join(format!("three_body_bookmarks_{}.ron", std::process::id()));
+    app.world().resource::<Config>().save(&path).unwrap();
+    let loaded = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.render.camera_bookmarks.get(&3), Some(&stored));
+
+    let mut app = app_with_camera(loaded, Vec3::new(0., 0., 100.), 1.);
+    common::tap(&mut app, KeyCode::Digit3);
+    let restored = view(&mut app);
+    assert!(
+        restored.center.distance(stored.center) < 1e-3,
+        "recalled {} instead of {}",
+        restored.center,
+        stored.center
+    );
+    assert_eq!(restored.scale, stored.scale);
+    assert_ne!(stored.center, DVec2::ZERO);
+}
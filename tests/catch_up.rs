@@ -0,0 +1,19 @@
+mod common;
+
+use std::time::Duration;
+use three_body::simulation::{Config, GravityPlugin, SimClock};
+
+#[test]
+fn a_long_frame_runs_at_most_max_catch_up_ticks() {
+    let config = Config { max_catch_up_ticks: 4, ..common::three_bodies() };
+    // A full second per frame: 64 ticks' worth at the default rate.
+    let mut app = common::app_with(GravityPlugin::new(config), Duration::from_secs(1));
+    let mut last = 0;
+    for _ in 0..5 {
+        app.update();
+        let steps = app.world().resource::<SimClock>().steps;
+        assert!(steps - last <= 4, "ran {} ticks in one frame", steps - last);
+        last = steps;
+    }
+    assert!(last >= 4 * 4, "only {last} ticks over five frames");
+}
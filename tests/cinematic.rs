@@ -0,0 +1,41 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::camera::CameraTarget;
+use three_body::cinematic::{CinematicPlugin, CinematicState};
+use three_body::diagnostics::DiagnosticsPlugin;
+use three_body::simulation::{SimClock, TimeDilation};
+
+#[test]
+fn time_slows_as_two_bodies_close_in() {
+    // Falling together from outside the default threshold of 5.
+    let config = common::natural(vec![
+        common::body(10., DVec3::new(-4., 0.1, 0.), DVec3::ZERO),
+        common::body(10., DVec3::new(4., -0.1, 0.), DVec3::ZERO),
+    ]);
+    // Simulated seconds per update, which runs one tick.
+    let full_speed = config.timestep / config.physics_hz;
+    let mut app = common::app(config);
+    app.insert_state(CinematicState::On)
+        .init_state::<CameraTarget>()
+        .add_plugins((DiagnosticsPlugin, CinematicPlugin));
+    app.update();
+
+    let mut last_dilation = app.world().resource::<TimeDilation>().0;
+    assert_eq!(last_dilation, 1., "bodies 8 apart should run at full speed");
+    let (mut distance, mut tick) = (f64::INFINITY, full_speed);
+    while distance > 1. {
+        let before = app.world().resource::<SimClock>().elapsed;
+        app.update();
+        tick = app.world().resource::<SimClock>().elapsed - before;
+        let dilation = app.world().resource::<TimeDilation>().0;
+        assert!(dilation <= last_dilation, "time sped up from {last_dilation} to {dilation} on the way in");
+        assert!(tick <= full_speed * (1. + 1e-9), "a tick covered {tick} s, more than at full speed");
+        last_dilation = dilation;
+        let bodies = common::bodies(app.world_mut());
+        distance = bodies[0].1.distance(bodies[1].1);
+    }
+    assert!(last_dilation < 0.5, "only slowed to {last_dilation} a unit apart");
+    assert!(tick < 0.5 * full_speed, "the last tick still covered {tick} s");
+}
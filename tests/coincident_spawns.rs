@@ -0,0 +1,38 @@
+use bevy::ecs::world::Command;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{BodyConfig, CoincidentSpawns, Config, Position, ResetSimulation};
+
+/// A world holding two bodies configured at the same point.
+fn spawn_twice(coincident_spawns: CoincidentSpawns) -> Vec<DVec3> {
+    let body = BodyConfig {
+        position: DVec3::new(1., 2., 0.),
+        ..default()
+    };
+    let config = Config {
+        initial_bodies: vec![body.clone(), body],
+        coincident_spawns,
+        ..default()
+    };
+    let mut world = World::new();
+    ResetSimulation(config).apply(&mut world);
+    world.query::<&Position>().iter(&world).map(|position| position.0).collect()
+}
+
+#[test]
+fn nudge_separates_by_the_minimum() {
+    let positions = spawn_twice(CoincidentSpawns::Nudge(0.01));
+    assert_eq!(positions.len(), 2);
+    assert!(positions[0].distance(positions[1]) >= 0.01 - 1e-12);
+}
+
+#[test]
+fn reject_drops_the_duplicate() {
+    assert_eq!(spawn_twice(CoincidentSpawns::Reject).len(), 1);
+}
+
+#[test]
+fn allow_keeps_both_on_top() {
+    let positions = spawn_twice(CoincidentSpawns::Allow);
+    assert_eq!(positions, [positions[0]; 2]);
+}
@@ -0,0 +1,120 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use std::time::Duration;
+use bevy::ecs::world::{Command, CommandQueue};
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use three_body::simulation::{
+    Body, BodyConfig, BodyId, Config, GravityPlugin, Position, ResetSimulation, SimulationState,
+    SpawnBodyCommandExt, Velocity,
+};
+use three_body::units::UnitSystem;
+
+/// A headless world running `config`.
+pub fn world(config: Config) -> World {
+    let mut world = World::new();
+    ResetSimulation(config).apply(&mut world);
+    world
+}
+
+/// A windowless app running `config` through `plugin`, already `Running`,
+/// whose every update advances time by `frame`.
+pub fn app_with(plugin: GravityPlugin, frame: Duration) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, InputPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(frame))
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_state(SimulationState::Running)
+        .add_plugins(plugin);
+    app
+}
+
+/// `app_with` the default `GravityPlugin` for `config`, one fixed tick per
+/// update.
+pub fn app(config: Config) -> App {
+    let frame = Duration::from_secs_f64(1. / config.physics_hz);
+    app_with(GravityPlugin::new(config), frame)
+}
+
+/// Taps `key`, an update each for the press and the release, so a state
+/// change it causes has been applied.
+pub fn tap(app: &mut App, key: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        app.world_mut().send_event(KeyboardInput {
+            key_code: key,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+    }
+}
+
+/// Trail entities: in a windowless app, the only meshed ones that aren't
+/// bodies.
+pub fn trails(app: &mut App) -> Vec<Entity> {
+    app.world_mut()
+        .query_filtered::<Entity, (With<Handle<Mesh>>, Without<Body>)>()
+        .iter(app.world())
+        .collect()
+}
+
+/// Spawns `body` the way the app's systems do, through `spawn_body`.
+pub fn spawn(world: &mut World, body: &BodyConfig) -> Entity {
+    let mut queue = CommandQueue::default();
+    let entity = Commands::new(&mut queue, world).spawn_body(body);
+    queue.apply(world);
+    entity
+}
+
+/// A body of `mass` at `position` moving at `velocity`, otherwise default.
+pub fn body(mass: f64, position: DVec3, velocity: DVec3) -> BodyConfig {
+    BodyConfig { mass, position, velocity, ..default() }
+}
+
+/// Default settings with `G = 1`, so small numbers make visible orbits.
+pub fn natural(initial_bodies: Vec<BodyConfig>) -> Config {
+    Config {
+        units: UnitSystem::Natural,
+        initial_bodies,
+        ..default()
+    }
+}
+
+/// Three unequal bodies on a tumbling, non-hierarchical orbit in 3D.
+pub fn three_bodies() -> Config {
+    natural(vec![
+        body(1., DVec3::new(1., 0., 0.), DVec3::new(0., 0.6, 0.05)),
+        body(0.8, DVec3::new(-0.8, 0.6, 0.1), DVec3::new(-0.4, -0.3, 0.)),
+        body(1.2, DVec3::new(-0.3, -0.9, -0.1), DVec3::new(0.3, -0.2, -0.04)),
+    ])
+}
+
+/// `(id, position, velocity)` of every body, in spawn order.
+pub fn bodies(world: &mut World) -> Vec<(BodyId, DVec3, DVec3)> {
+    let mut bodies: Vec<_> = world
+        .query::<(&BodyId, &Position, &Velocity)>()
+        .iter(world)
+        .map(|(id, position, velocity)| (*id, position.0, velocity.0))
+        .collect();
+    bodies.sort_by_key(|(id, _, _)| *id);
+    bodies
+}
+
+/// `(mass, position, velocity)` of every body in spawn order, the masses
+/// as configured, for `three_body::diagnostics`.
+pub fn states(world: &mut World) -> Vec<(f64, DVec3, DVec3)> {
+    let mut states: Vec<_> = world
+        .query::<(&BodyId, &BodyConfig, &Position, &Velocity)>()
+        .iter(world)
+        .map(|(id, config, position, velocity)| (*id, config.mass, position.0, velocity.0))
+        .collect();
+    states.sort_by_key(|(id, ..)| *id);
+    states.into_iter().map(|(_, mass, position, velocity)| (mass, position, velocity)).collect()
+}
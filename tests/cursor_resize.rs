@@ -0,0 +1,43 @@
+mod common;
+
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use three_body::cursor::{CursorCoords, CursorPlugin, MainCamera};
+use three_body::simulation::Config;
+
+/// World position `CursorCoords` reports with the window at `size` and the
+/// cursor `offset` logical pixels right of and above its center.
+fn world_under(app: &mut App, window: Entity, size: Vec2, offset: Vec2) -> DVec2 {
+    let mut entity = app.world_mut().entity_mut(window);
+    let mut window = entity.get_mut::<Window>().unwrap();
+    window.resolution.set(size.x, size.y);
+    window.set_cursor_position(Some(size / 2. + Vec2::new(offset.x, -offset.y)));
+    app.update();
+    app.world().resource::<CursorCoords>().world()
+}
+
+#[test]
+fn cursor_maps_to_the_same_world_point_after_a_resize() {
+    // Keeps the render origin put; the camera here has no `Transform` to move.
+    let config = Config { floating_origin: false, ..common::three_bodies() };
+    let mut app = common::app(config);
+    app.add_plugins(CursorPlugin);
+    let window = app.world_mut().spawn((Window::default(), PrimaryWindow)).id();
+    let projection = OrthographicProjection { scale: 0.5, ..default() };
+    app.world_mut().spawn((
+        MainCamera,
+        GlobalTransform::from_translation(Vec3::new(20., -10., 100.)),
+        Projection::Orthographic(projection),
+    ));
+    app.update();
+
+    for offset in [Vec2::ZERO, Vec2::new(100., -60.)] {
+        let before = world_under(&mut app, window, Vec2::new(800., 600.), offset);
+        let after = world_under(&mut app, window, Vec2::new(1200., 500.), offset);
+        // The projection keeps a fixed scale per pixel, centered on the camera.
+        let expected = (Vec2::new(20., -10.) + offset * 0.5).as_dvec2();
+        assert!(before.distance(expected) < 1e-3, "mapped to {before}, expected {expected}");
+        assert!(after.distance(before) < 1e-3, "moved from {before} to {after} on resize");
+    }
+}
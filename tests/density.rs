@@ -0,0 +1,28 @@
+mod common;
+
+use bevy::math::{DVec2, DVec3};
+use bevy::prelude::*;
+use three_body::density::{DensityMap, DensityPlugin, DensityState};
+use three_body::simulation::{BodyConfig, SimClock};
+
+#[test]
+fn the_cell_under_a_still_body_counts_every_tick() {
+    let star = BodyConfig { anchored: true, ..common::body(1., DVec3::new(1.2, -0.7, 0.), DVec3::ZERO) };
+    let planet = common::body(1e-3, DVec3::new(2.2, -0.7, 0.), DVec3::new(0., 1., 0.));
+    let mut app = common::app(common::natural(vec![star, planet]));
+    app.init_resource::<Assets<Image>>()
+        .insert_state(DensityState::Show)
+        .add_plugins(DensityPlugin);
+
+    let under_star = DVec2::new(1.2, -0.7);
+    let mut last = 0;
+    for _ in 0..50 {
+        app.update();
+        let count = app.world().resource::<DensityMap>().count_at(under_star);
+        assert!(count >= last, "the count fell from {last} to {count}");
+        last = count;
+    }
+    let ticks = app.world().resource::<SimClock>().steps;
+    assert!(ticks > 0);
+    assert_eq!(u64::from(last), ticks, "one count per tick");
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use bevy::math::DVec3;
+use three_body::diagnostics::total_energy;
+use three_body::simulation::{step, Config};
+
+#[test]
+fn drag_drains_energy_every_tick() {
+    // A circular orbit, whose integration error in energy is smallest.
+    let speed = 0.5_f64.sqrt();
+    let config = Config {
+        drag_coefficient: 0.05,
+        substeps: 8,
+        ..common::natural(vec![
+            common::body(1., DVec3::new(-0.5, 0., 0.), DVec3::new(0., -speed, 0.)),
+            common::body(1., DVec3::new(0.5, 0., 0.), DVec3::new(0., speed, 0.)),
+        ])
+    };
+    let g = config.gravitational_constant();
+    let mut world = common::world(config);
+    let start = total_energy(&common::states(&mut world), g);
+    let mut last = start;
+    for tick in 0..1000 {
+        step(&mut world, 1);
+        let energy = total_energy(&common::states(&mut world), g);
+        assert!(energy < last, "energy rose from {last} to {energy} at tick {tick}");
+        last = energy;
+    }
+    assert!(start - last > 0.1 * start.abs(), "only fell from {start} to {last}");
+}
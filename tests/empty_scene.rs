@@ -0,0 +1,46 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::cursor::CursorPlugin;
+use three_body::diagnostics::DiagnosticsPlugin;
+use three_body::orbit::OrbitPlugin;
+use three_body::overlay::OverlayPlugin;
+use three_body::profiling::ProfilingPlugin;
+use three_body::scenario::ScenarioLibrary;
+use three_body::simulation::Config;
+
+const PROMPT: &str = "No bodies: click Left mouse to add one";
+
+/// Texts shown, neither they nor their parent hidden, by first section.
+/// Headless apps don't propagate visibility, so this checks by hand.
+fn shown_texts(app: &mut App) -> Vec<String> {
+    let world = app.world_mut();
+    let texts: Vec<_> = world.query::<(&Text, &Visibility, Option<&Parent>)>()
+        .iter(world)
+        .map(|(text, visibility, parent)| (text.sections[0].value.clone(), *visibility, parent.map(Parent::get)))
+        .collect();
+    texts.into_iter()
+        .filter(|(_, visibility, parent)| {
+            let parent_visibility = parent.and_then(|parent| world.get::<Visibility>(parent));
+            *visibility != Visibility::Hidden && parent_visibility != Some(&Visibility::Hidden)
+        })
+        .map(|(text, ..)| text)
+        .collect()
+}
+
+#[test]
+fn an_empty_scene_runs_and_prompts_for_bodies() {
+    let mut app = common::app(Config::default());
+    app.init_resource::<ScenarioLibrary>()
+        .add_plugins((CursorPlugin, OrbitPlugin, DiagnosticsPlugin, ProfilingPlugin, OverlayPlugin));
+    for _ in 0..5 {
+        app.update();
+    }
+    let texts = shown_texts(&mut app);
+    assert!(texts.iter().any(|text| text == PROMPT), "no prompt among {texts:?}");
+
+    common::spawn(app.world_mut(), &common::body(1., DVec3::ZERO, DVec3::ZERO));
+    app.update();
+    assert!(!shown_texts(&mut app).iter().any(|text| text == PROMPT), "the prompt should go with a body");
+}
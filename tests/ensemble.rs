@@ -0,0 +1,44 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{step, BodyConfig, BodyId, Config, EnsembleConfig, Position};
+
+const PERTURBATION: f64 = 1e-6;
+
+/// Positions of each copy's bodies, by copy and then configured order.
+fn copies(world: &mut World) -> Vec<Vec<DVec3>> {
+    let mut bodies: Vec<_> = world
+        .query::<(&BodyId, &BodyConfig, &Position)>()
+        .iter(world)
+        .map(|(id, body, position)| (body.instance.unwrap().id, *id, position.0))
+        .collect();
+    bodies.sort_by_key(|(copy, id, _)| (*copy, *id));
+    bodies.chunk_by(|a, b| a.0 == b.0)
+        .map(|copy| copy.iter().map(|(_, _, position)| *position).collect())
+        .collect()
+}
+
+/// Furthest any body of a copy is from its counterpart in the first one.
+fn spread(copies: &[Vec<DVec3>]) -> f64 {
+    copies[1..].iter()
+        .flat_map(|copy| copy.iter().zip(&copies[0]).map(|(a, b)| a.distance(*b)))
+        .fold(0., f64::max)
+}
+
+#[test]
+fn copies_start_close_and_drift_apart() {
+    let mut world = common::world(Config {
+        ensemble: Some(EnsembleConfig { count: 4, perturbation: PERTURBATION, seed: 3 }),
+        ..common::three_bodies()
+    });
+    let start = copies(&mut world);
+    assert_eq!(start.len(), 4);
+    assert!(start.iter().all(|copy| copy.len() == 3));
+    let initial = spread(&start);
+    assert!(initial > 0. && initial <= PERTURBATION * (1. + 1e-9), "copies start {initial} apart");
+
+    step(&mut world, 3000);
+    let later = spread(&copies(&mut world));
+    assert!(later > 100. * initial, "copies only drifted from {initial:e} to {later:e}");
+}
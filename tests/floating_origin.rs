@@ -0,0 +1,33 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{Body, Config, Position, RenderOrigin};
+
+#[test]
+fn far_bodies_render_near_the_origin_without_jitter() {
+    let far = DVec3::splat(1e6);
+    let config = Config {
+        floating_origin: true,
+        ..common::natural(vec![
+            common::body(1., far + DVec3::new(1., 0., 0.), DVec3::new(0., 0.5, 0.)),
+            common::body(1., far - DVec3::new(1., 0., 0.), DVec3::new(0., -0.5, 0.)),
+        ])
+    };
+    let mut app = common::app(config);
+    // The origin first moves on the first tick, which the first frame,
+    // having no elapsed time, doesn't run.
+    app.update();
+    for _ in 0..200 {
+        app.update();
+        let origin = *app.world().resource::<RenderOrigin>();
+        let mut query = app.world_mut().query_filtered::<(&Position, &Transform), With<Body>>();
+        for (position, transform) in query.iter(app.world()) {
+            let offset = transform.translation;
+            assert!(offset.length() < 2., "rendered {offset} from the origin");
+            // At 1e6 an f32 only resolves steps of about 0.06.
+            let error = (position.0 - origin.0 - offset.as_dvec3()).length();
+            assert!(error < 1e-5, "rendered {error} off its position");
+        }
+    }
+}
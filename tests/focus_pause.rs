@@ -0,0 +1,47 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowFocused};
+use three_body::simulation::{SimulationPaused, SimulationResumed, SimulationState};
+
+#[derive(Resource, Default)]
+struct Counts {
+    paused: usize,
+    resumed: usize,
+}
+
+fn count(
+    mut counts: ResMut<Counts>,
+    mut paused: EventReader<SimulationPaused>,
+    mut resumed: EventReader<SimulationResumed>,
+) {
+    counts.paused += paused.read().count();
+    counts.resumed += resumed.read().count();
+}
+
+fn focus(app: &mut App, window: Entity, focused: bool) {
+    app.world_mut().send_event(WindowFocused { window, focused });
+    for _ in 0..5 {
+        app.update();
+    }
+}
+
+#[test]
+fn one_focus_change_pauses_and_resumes_once() {
+    let mut app = common::app(common::three_bodies());
+    app.init_resource::<Counts>().add_systems(Last, count);
+    let window = app.world_mut().spawn(PrimaryWindow).id();
+    app.update();
+    let counts = app.world().resource::<Counts>();
+    assert_eq!((counts.paused, counts.resumed), (0, 0));
+
+    focus(&mut app, window, false);
+    assert_eq!(app.world().resource::<State<SimulationState>>().get(), &SimulationState::Stopped);
+    let counts = app.world().resource::<Counts>();
+    assert_eq!((counts.paused, counts.resumed), (1, 0));
+
+    focus(&mut app, window, true);
+    assert_eq!(app.world().resource::<State<SimulationState>>().get(), &SimulationState::Running);
+    let counts = app.world().resource::<Counts>();
+    assert_eq!((counts.paused, counts.resumed), (1, 1));
+}
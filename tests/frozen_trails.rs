@@ -0,0 +1,42 @@
+mod common;
+
+use bevy::prelude::*;
+use three_body::trail::TrailState;
+
+/// Vertices in each trail's mesh, one per recorded point for line trails.
+fn trail_lengths(app: &mut App) -> Vec<usize> {
+    let mut lengths: Vec<usize> = common::trails(app)
+        .into_iter()
+        .map(|trail| {
+            let handle = app.world().get::<Handle<Mesh>>(trail).unwrap();
+            app.world().resource::<Assets<Mesh>>().get(handle).unwrap().count_vertices()
+        })
+        .collect();
+    lengths.sort();
+    lengths
+}
+
+#[test]
+fn frozen_trails_stop_growing_while_bodies_move() {
+    let mut app = common::app(common::three_bodies());
+    for _ in 0..10 {
+        app.update();
+    }
+    let shown = trail_lengths(&mut app);
+    app.update();
+    let grown = trail_lengths(&mut app);
+    assert!(
+        grown.iter().zip(&shown).all(|(now, before)| now > before),
+        "shown trails should grow: {shown:?} -> {grown:?}",
+    );
+
+    app.world_mut().resource_mut::<NextState<TrailState>>().set(TrailState::Freeze);
+    app.update();
+    let frozen = trail_lengths(&mut app);
+    let bodies = common::bodies(app.world_mut());
+    for _ in 0..20 {
+        app.update();
+    }
+    assert_ne!(common::bodies(app.world_mut()), bodies, "the bodies should keep moving");
+    assert_eq!(trail_lengths(&mut app), frozen);
+}
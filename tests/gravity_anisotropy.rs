@@ -0,0 +1,44 @@
+use bevy::ecs::world::Command;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{step, BodyConfig, BodyId, Config, ResetSimulation, Velocity};
+use three_body::units::UnitSystem;
+
+/// Z velocities after a run of three bodies spread out along every axis,
+/// with gravity along Z switched off.
+fn z_velocities(parallel_gravity: bool) -> (Vec<f64>, Vec<f64>) {
+    let body = |position: DVec3, velocity: DVec3| BodyConfig { mass: 1., position, velocity, ..default() };
+    let config = Config {
+        units: UnitSystem::Natural,
+        initial_bodies: vec![
+            body(DVec3::new(1., 0., 0.5), DVec3::new(0., 0.5, 0.1)),
+            body(DVec3::new(-1., 0.2, -0.5), DVec3::new(0., -0.5, 0.)),
+            body(DVec3::new(0., 1., 1.), DVec3::new(-0.3, 0., -0.2)),
+        ],
+        gravity_anisotropy: Some(DVec3::new(1., 1., 0.)),
+        parallel_gravity,
+        ..default()
+    };
+    let initial = config.initial_bodies.iter().map(|body| body.velocity.z).collect();
+    let mut world = World::new();
+    ResetSimulation(config).apply(&mut world);
+    step(&mut world, 500);
+    let mut bodies: Vec<(BodyId, f64)> = world.query::<(&BodyId, &Velocity)>()
+        .iter(&world)
+        .map(|(id, velocity)| (*id, velocity.0.z))
+        .collect();
+    bodies.sort_by_key(|(id, _)| *id);
+    (initial, bodies.into_iter().map(|(_, z)| z).collect())
+}
+
+#[test]
+fn zero_z_scale_keeps_z_velocities() {
+    let (initial, after) = z_velocities(false);
+    assert_eq!(initial, after);
+}
+
+#[test]
+fn zero_z_scale_keeps_z_velocities_in_parallel() {
+    let (initial, after) = z_velocities(true);
+    assert_eq!(initial, after);
+}
@@ -0,0 +1,32 @@
+use bevy::math::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use three_body::nbody::ForceLaw;
+
+/// Pairs among `points` that `law` evaluates a force for.
+fn interacting_pairs(law: &ForceLaw, points: &[DVec3]) -> usize {
+    (0..points.len())
+        .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| law.factor(points[j] - points[i], 0.).is_some())
+        .count()
+}
+
+#[test]
+fn cutoff_drops_the_far_pairs_of_a_spread_out_scene() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let points: Vec<DVec3> = (0..200)
+        .map(|_| DVec3::new(rng.gen_range(0.0..100.), rng.gen_range(0.0..100.), rng.gen_range(0.0..100.)))
+        .collect();
+    let all = points.len() * (points.len() - 1) / 2;
+    assert_eq!(interacting_pairs(&ForceLaw::newtonian(1.), &points), all);
+
+    let cutoff = 20.;
+    let within = (0..points.len())
+        .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| points[i].distance(points[j]) <= cutoff)
+        .count();
+    let law = ForceLaw::new(1., 2., Some(cutoff), false);
+    assert_eq!(interacting_pairs(&law, &points), within);
+    // A sphere of radius 20 is about 3% of the 100³ box; edges trim it further.
+    assert!(within * 20 < all, "{within} of {all} pairs within the cutoff");
+}
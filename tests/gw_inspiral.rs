@@ -0,0 +1,40 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{step, BodyConfig, Config};
+
+/// Equal masses a unit apart on a circular orbit, with `c` four times their
+/// relative speed, so the inspiral is visible within a few orbits.
+fn circular_binary() -> Config {
+    let speed = 0.5_f64.sqrt();
+    let body = |side: f64| BodyConfig {
+        radius: 0.05,
+        ..common::body(1., DVec3::new(side * 0.5, 0., 0.), DVec3::new(0., side * speed, 0.))
+    };
+    Config {
+        gw_inspiral: true,
+        gw_light_speed: 8. * speed,
+        substeps: 8,
+        ..common::natural(vec![body(-1.), body(1.)])
+    }
+}
+
+fn separation(world: &mut World) -> f64 {
+    let bodies = common::bodies(world);
+    bodies[0].1.distance(bodies[1].1)
+}
+
+#[test]
+fn a_circular_binary_spirals_in() {
+    let mut world = common::world(circular_binary());
+    let mut last = separation(&mut world);
+    for tick in 0..1000 {
+        step(&mut world, 1);
+        let now = separation(&mut world);
+        assert!(now < last, "separation grew from {last} to {now} at tick {tick}");
+        last = now;
+    }
+    // Three and a half orbits take about a tenth off.
+    assert!(last < 0.95, "only shrank to {last}");
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use std::time::Duration;
+use bevy::ecs::world::Command;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use three_body::simulation::{GravityPlugin, ResetSimulation, SimulationPaused, SimulationState};
+use three_body::trail::TrailState;
+
+/// An app whose host initializes the states and `Time<Fixed>` itself, at
+/// values the plugin wouldn't pick, before adding the plugin.
+fn host_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, InputPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(10)))
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_state(SimulationState::Running)
+        .insert_state(TrailState::Freeze)
+        .insert_resource(Time::<Fixed>::from_hz(30.))
+        .add_plugins(
+            GravityPlugin::new(common::three_bodies())
+                .manage_states(false)
+                .manage_fixed_timestep(false),
+        );
+    app
+}
+
+#[test]
+fn host_states_are_left_alone() {
+    let mut app = host_app();
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<State<SimulationState>>().get(), &SimulationState::Running);
+    assert_eq!(app.world().resource::<State<TrailState>>().get(), &TrailState::Freeze);
+
+    // Registered once, a pause sends one event.
+    app.world_mut().resource_mut::<NextState<SimulationState>>().set(SimulationState::Stopped);
+    app.update();
+    assert_eq!(app.world().resource::<State<SimulationState>>().get(), &SimulationState::Stopped);
+    assert_eq!(app.world().resource::<Events<SimulationPaused>>().len(), 1);
+}
+
+#[test]
+fn host_fixed_timestep_survives_a_reset() {
+    let mut app = host_app();
+    app.update();
+    ResetSimulation(common::three_bodies()).apply(app.world_mut());
+    assert_eq!(app.world().resource::<Time<Fixed>>().timestep(), Duration::from_secs_f64(1. / 30.));
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use bevy::math::DVec3;
+use three_body::simulation::step;
+
+/// Speed a light body at rest `r` from a body of `mass` picks up in one tick.
+fn pull(mass: f64, r: f64) -> f64 {
+    let config = common::natural(vec![
+        common::body(mass, DVec3::ZERO, DVec3::ZERO),
+        common::body(1e-9, DVec3::X * r, DVec3::ZERO),
+    ]);
+    let dt = config.timestep / config.physics_hz;
+    let mut world = common::world(config);
+    step(&mut world, 1);
+    common::bodies(&mut world)[1].2.length() / dt
+}
+
+#[test]
+fn mass_induces_g_m_over_r_squared() {
+    for (mass, r) in [(1., 2.), (3., 2.), (3., 5.)] {
+        // `natural` units have G = 1.
+        let expected = mass / (r * r);
+        let acceleration = pull(mass, r);
+        assert!(
+            (acceleration - expected).abs() < expected * 1e-6,
+            "mass {mass} at {r} pulled with {acceleration}, expected {expected}"
+        );
+    }
+}
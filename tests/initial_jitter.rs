@@ -0,0 +1,51 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{step, Config};
+
+fn jittered(initial_jitter: Option<(f64, u64)>) -> Config {
+    Config { initial_jitter, ..common::three_bodies() }
+}
+
+/// `(position, velocity)` of every body, in spawn order.
+fn states(world: &mut World) -> Vec<(DVec3, DVec3)> {
+    common::bodies(world).into_iter().map(|(_, position, velocity)| (position, velocity)).collect()
+}
+
+/// Bodies as spawned for `config`, then after a short run.
+fn run(config: Config) -> [Vec<(DVec3, DVec3)>; 2] {
+    let mut world = common::world(config);
+    let spawned = states(&mut world);
+    step(&mut world, 100);
+    [spawned, states(&mut world)]
+}
+
+fn configured(config: &Config) -> Vec<(DVec3, DVec3)> {
+    config.initial_bodies.iter().map(|body| (body.position, body.velocity)).collect()
+}
+
+#[test]
+fn the_same_seed_gives_the_same_run() {
+    assert_eq!(run(jittered(Some((0.01, 7)))), run(jittered(Some((0.01, 7)))));
+    assert_ne!(run(jittered(Some((0.01, 7))))[0], run(jittered(Some((0.01, 8))))[0]);
+}
+
+#[test]
+fn jitter_stays_within_its_magnitude() {
+    let config = jittered(Some((0.01, 7)));
+    let [spawned, _] = run(config.clone());
+    for ((position, velocity), (from_position, from_velocity)) in spawned.into_iter().zip(configured(&config)) {
+        for offset in [position - from_position, velocity - from_velocity] {
+            assert!(offset.abs().max_element() <= 0.01, "nudged by {offset}");
+            assert_eq!(offset.z, 0.);
+        }
+    }
+}
+
+#[test]
+fn zero_magnitude_leaves_bodies_exact() {
+    let config = jittered(Some((0., 7)));
+    let [spawned, _] = run(config.clone());
+    assert_eq!(spawned, configured(&config));
+}
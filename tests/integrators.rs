@@ -0,0 +1,57 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::diagnostics::total_energy;
+use three_body::simulation::{step, BodyConfig, Integrator, Position, SimInstance, Velocity};
+
+/// The same eccentric binary twice on top of itself, once per integrator.
+/// Instances don't attract each other, so the copies run independently.
+fn side_by_side() -> Vec<BodyConfig> {
+    [(0, Integrator::SemiImplicitEuler), (1, Integrator::Euler)]
+        .into_iter()
+        .flat_map(|(id, integrator)| {
+            let instance = Some(SimInstance { id, integrator: Some(integrator) });
+            [(-0.5, -0.8), (0.5, 0.8)].map(|(x, speed)| BodyConfig {
+                instance,
+                ..common::body(1., DVec3::new(x, 0., 0.), DVec3::new(0., speed, 0.))
+            })
+        })
+        .collect()
+}
+
+/// `total_energy` of each instance's bodies, by instance id.
+fn energies(world: &mut World, g: f64) -> [f64; 2] {
+    let bodies: Vec<_> = world
+        .query::<(&BodyConfig, &Position, &Velocity)>()
+        .iter(world)
+        .map(|(body, position, velocity)| (body.instance.unwrap().id, (body.mass, position.0, velocity.0)))
+        .collect();
+    [0, 1].map(|id| {
+        let states: Vec<_> = bodies.iter()
+            .filter(|(instance, _)| *instance == id)
+            .map(|(_, state)| *state)
+            .collect();
+        total_energy(&states, g)
+    })
+}
+
+#[test]
+fn euler_drifts_where_semi_implicit_euler_holds() {
+    let config = common::natural(side_by_side());
+    let g = config.gravitational_constant();
+    let mut world = common::world(config);
+    let start = energies(&mut world, g);
+    assert_eq!(start[0], start[1]);
+
+    let mut largest = [0_f64; 2];
+    for _ in 0..10 {
+        step(&mut world, 64);
+        let now = energies(&mut world, g);
+        for i in 0..2 {
+            largest[i] = largest[i].max(((now[i] - start[i]) / start[i]).abs());
+        }
+    }
+    let [semi_implicit, euler] = largest;
+    assert!(euler > 10. * semi_implicit, "Euler drifted {euler:e}, semi-implicit Euler {semi_implicit:e}");
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use bevy::prelude::*;
+use three_body::input::{Action, Binding, KeyBindings};
+use three_body::simulation::SimulationState;
+
+fn state(app: &App) -> SimulationState {
+    app.world().resource::<State<SimulationState>>().get().clone()
+}
+
+#[test]
+fn a_remapped_pause_key_takes_over() {
+    let mut app = common::app(common::three_bodies());
+    app.update();
+    assert_eq!(state(&app), SimulationState::Running);
+    common::tap(&mut app, KeyCode::Space);
+    assert_eq!(state(&app), SimulationState::Stopped, "the default binding should pause");
+
+    app.world_mut().resource_mut::<KeyBindings>().rebind(Action::ToggleSimulation, Binding::Key(KeyCode::KeyQ));
+    common::tap(&mut app, KeyCode::Space);
+    assert_eq!(state(&app), SimulationState::Stopped, "the old key should do nothing");
+    common::tap(&mut app, KeyCode::KeyQ);
+    assert_eq!(state(&app), SimulationState::Running, "the new key should resume");
+}
@@ -0,0 +1,50 @@
+use bevy::ecs::world::Command;
+use bevy::math::{DVec2, DVec3};
+use bevy::prelude::*;
+use three_body::launch::{BeginLaunch, CancelLaunch, CommitLaunch, LaunchGhost};
+use three_body::simulation::{Body, Config, Position, ResetSimulation, Velocity};
+
+fn world() -> World {
+    let mut world = World::new();
+    ResetSimulation(Config { drag_launch: Some(1.), ..default() }).apply(&mut world);
+    world
+}
+
+fn ghosts(world: &mut World) -> Vec<LaunchGhost> {
+    world.query::<&LaunchGhost>().iter(world).copied().collect()
+}
+
+fn bodies(world: &mut World) -> Vec<(DVec3, DVec3)> {
+    world.query_filtered::<(&Position, &Velocity), With<Body>>()
+        .iter(world)
+        .map(|(position, velocity)| (position.0, velocity.0))
+        .collect()
+}
+
+#[test]
+fn press_creates_a_ghost_only() {
+    let mut world = world();
+    BeginLaunch(DVec2::new(3., 4.)).apply(&mut world);
+    assert_eq!(ghosts(&mut world), [LaunchGhost { position: DVec3::new(3., 4., 0.), velocity: DVec3::ZERO }]);
+    assert!(bodies(&mut world).is_empty());
+}
+
+#[test]
+fn release_spawns_one_body_and_removes_the_ghost() {
+    let mut world = world();
+    BeginLaunch(DVec2::new(3., 4.)).apply(&mut world);
+    world.query::<&mut LaunchGhost>().single_mut(&mut world).velocity = DVec3::X;
+    CommitLaunch.apply(&mut world);
+    assert!(ghosts(&mut world).is_empty());
+    assert_eq!(bodies(&mut world), [(DVec3::new(3., 4., 0.), DVec3::X)]);
+}
+
+#[test]
+fn cancel_spawns_nothing() {
+    let mut world = world();
+    BeginLaunch(DVec2::new(3., 4.)).apply(&mut world);
+    CancelLaunch.apply(&mut world);
+    CommitLaunch.apply(&mut world);
+    assert!(ghosts(&mut world).is_empty());
+    assert!(bodies(&mut world).is_empty());
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use bevy::ecs::world::Command;
+use bevy::math::{DVec2, DVec3};
+use bevy::prelude::*;
+use three_body::launch::{BeginLaunch, CommitLaunch};
+use three_body::simulation::{BodyId, Config};
+
+/// Launches a body at `x` on the x axis, the way a click-drag spawns one.
+fn launch(world: &mut World, x: f64) {
+    BeginLaunch(DVec2::new(x, 0.)).apply(world);
+    CommitLaunch.apply(world);
+}
+
+fn ids(world: &mut World) -> Vec<BodyId> {
+    common::bodies(world).into_iter().map(|(id, _, _)| id).collect()
+}
+
+#[test]
+fn spawning_at_the_cap_evicts_the_oldest() {
+    let mut world = common::world(Config {
+        max_bodies: Some(3),
+        ..common::natural(vec![common::body(1., DVec3::ZERO, DVec3::ZERO)])
+    });
+    launch(&mut world, 1.);
+    launch(&mut world, 2.);
+    assert_eq!(ids(&mut world), [BodyId(0), BodyId(1), BodyId(2)]);
+
+    // The initial body is exempt, so the oldest spawned one goes each time.
+    launch(&mut world, 3.);
+    assert_eq!(ids(&mut world), [BodyId(0), BodyId(2), BodyId(3)]);
+    launch(&mut world, 4.);
+    assert_eq!(ids(&mut world), [BodyId(0), BodyId(3), BodyId(4)]);
+}
+
+#[test]
+fn a_lowered_cap_is_restored_by_the_next_spawn() {
+    let mut world = common::world(Config {
+        max_bodies: Some(4),
+        ..common::natural(vec![common::body(1., DVec3::ZERO, DVec3::ZERO)])
+    });
+    for x in 1..=3 {
+        launch(&mut world, f64::from(x));
+    }
+    world.resource_mut::<Config>().max_bodies = Some(2);
+    launch(&mut world, 4.);
+    assert_eq!(ids(&mut world), [BodyId(0), BodyId(4)]);
+}
+
+#[test]
+fn nothing_spawns_when_only_exempt_bodies_are_left() {
+    let mut world = common::world(Config {
+        max_bodies: Some(1),
+        ..common::natural(vec![common::body(1., DVec3::ZERO, DVec3::ZERO)])
+    });
+    launch(&mut world, 1.);
+    assert_eq!(ids(&mut world), [BodyId(0)]);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use bevy::math::DVec3;
+use three_body::simulation::{step, Config};
+
+/// Two bodies dropped almost head-on, so they swing through a deep,
+/// fast periapsis.
+fn close_encounter(max_speed: Option<f64>) -> Config {
+    Config {
+        max_speed,
+        ..common::natural(vec![
+            common::body(1., DVec3::new(-1., 0.01, 0.), DVec3::ZERO),
+            common::body(1., DVec3::new(1., -0.01, 0.), DVec3::ZERO),
+        ])
+    }
+}
+
+/// Highest speed any body reaches over ten seconds, checked every tick.
+fn peak_speed(config: Config) -> f64 {
+    let mut world = common::world(config);
+    let mut peak: f64 = 0.;
+    for _ in 0..640 {
+        step(&mut world, 1);
+        for (_, _, velocity) in common::bodies(&mut world) {
+            peak = peak.max(velocity.length());
+        }
+    }
+    peak
+}
+
+#[test]
+fn no_body_outruns_max_speed() {
+    const CAP: f64 = 2.;
+    let uncapped = peak_speed(close_encounter(None));
+    assert!(uncapped > 2. * CAP, "the encounter should be fast without the cap, reached {uncapped}");
+    let peak = peak_speed(close_encounter(Some(CAP)));
+    assert!(peak <= CAP, "reached {peak}");
+}
@@ -0,0 +1,27 @@
+use bevy::math::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use three_body::spatial::SpatialGrid;
+
+#[test]
+fn clustered_scene_evaluates_only_pairs_within_clusters() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let centers = [DVec3::ZERO, DVec3::X * 100., DVec3::Y * 100., DVec3::new(100., 100., 0.)];
+    let per_cluster = 25;
+    let points: Vec<DVec3> = centers.iter()
+        .flat_map(|center| {
+            (0..per_cluster)
+                .map(|_| *center + DVec3::new(rng.gen_range(-2.0..2.), rng.gen_range(-2.0..2.), rng.gen_range(-2.0..2.)))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    // The near pairs `gravity` hands `accumulate_mean_field` at this cutoff.
+    let cutoff = 10.;
+    let near = SpatialGrid::from_points(cutoff, &points).candidate_pairs(cutoff);
+    let within_clusters = centers.len() * per_cluster * (per_cluster - 1) / 2;
+    let all = points.len() * (points.len() - 1) / 2;
+    assert_eq!(near.len(), within_clusters, "every pair within a cluster, and none across");
+    assert!(near.iter().all(|&(i, j)| i / per_cluster == j / per_cluster));
+    assert!(near.len() * 4 <= all, "{} of {all} pairs evaluated exactly", near.len());
+}
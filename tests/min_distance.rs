@@ -0,0 +1,27 @@
+mod common;
+
+use bevy::math::DVec3;
+use three_body::diagnostics::{DiagnosticsPlugin, MinDistance};
+use three_body::simulation::{BodyId, BodyIndex};
+
+#[test]
+fn the_closest_of_three_pairs_is_reported() {
+    // Light enough to stay put: the pairs are 10, 3 and about 10.4 apart.
+    let mut app = common::app(common::natural(vec![
+        common::body(1e-9, DVec3::ZERO, DVec3::ZERO),
+        common::body(1e-9, DVec3::new(10., 0., 0.), DVec3::ZERO),
+        common::body(1e-9, DVec3::new(10., 3., 0.), DVec3::ZERO),
+    ]));
+    app.add_plugins(DiagnosticsPlugin);
+    for _ in 0..3 {
+        app.update();
+    }
+    let pair = app.world().resource::<MinDistance>().0.expect("three bodies should have a closest pair");
+    let index = app.world().resource::<BodyIndex>();
+    let mut found = [pair.a, pair.b];
+    found.sort();
+    let mut expected = [index.get(BodyId(1)).unwrap(), index.get(BodyId(2)).unwrap()];
+    expected.sort();
+    assert_eq!(found, expected);
+    assert!((pair.distance - 3.).abs() < 1e-6, "reported {}", pair.distance);
+}
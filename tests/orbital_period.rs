@@ -0,0 +1,35 @@
+mod common;
+
+use std::f64::consts::TAU;
+use bevy::math::DVec3;
+use three_body::orbit::DominantPairOrbit;
+use three_body::simulation::{step, TimeDilation};
+
+#[test]
+fn real_time_period_follows_the_time_dilation() {
+    // A light planet on a circular orbit of radius 1 about a star of mass 1:
+    // T = 2π simulated seconds.
+    let config = common::natural(vec![
+        common::body(1., DVec3::ZERO, DVec3::ZERO),
+        common::body(1e-9, DVec3::X, DVec3::Y),
+    ]);
+    let DominantPairOrbit::Bound { period, .. } = DominantPairOrbit::from_state(DVec3::X, DVec3::Y, 1.) else {
+        panic!("the orbit should be bound");
+    };
+    assert!((period - TAU).abs() < 1e-9);
+
+    let dilation = TimeDilation(0.25);
+    let real = dilation.real_seconds(&config, period);
+    assert!((real - TAU / (config.timestep * 0.25)).abs() < 1e-9);
+
+    // Run for that many real seconds of ticks: the planet is back at the start.
+    let ticks = (real * config.physics_hz).round() as usize;
+    let mut world = common::world(config.clone());
+    world.insert_resource(dilation);
+    step(&mut world, ticks);
+    let planet = common::bodies(&mut world)[1].1;
+    assert!(planet.distance(DVec3::X) < 0.05, "the planet ended at {planet}");
+    step(&mut world, ticks / 2);
+    let planet = common::bodies(&mut world)[1].1;
+    assert!(planet.distance(-DVec3::X) < 0.05, "half a period later the planet is at {planet}");
+}
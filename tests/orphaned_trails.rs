@@ -0,0 +1,64 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{BodyId, BodyIndex, Config, OrphanedTrails};
+
+/// Two bodies orbiting each other, with `orphaned_trails` applied.
+fn config(orphaned_trails: OrphanedTrails) -> Config {
+    Config {
+        orphaned_trails,
+        ..common::natural(vec![
+            common::body(1., DVec3::new(-1., 0., 0.), DVec3::new(0., -0.35, 0.)),
+            common::body(1., DVec3::new(1., 0., 0.), DVec3::new(0., 0.35, 0.)),
+        ])
+    }
+}
+
+/// An app that has run long enough for both bodies to lay down a trail.
+fn running(orphaned_trails: OrphanedTrails) -> App {
+    let mut app = common::app(config(orphaned_trails));
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(common::trails(&mut app).len(), 2);
+    app
+}
+
+fn despawn_body(app: &mut App, id: BodyId) {
+    let entity = app.world().resource::<BodyIndex>().get(id).expect("body should exist");
+    app.world_mut().entity_mut(entity).despawn_recursive();
+}
+
+#[test]
+fn despawning_a_body_leaves_no_trail() {
+    let mut app = running(OrphanedTrails::Despawn);
+    despawn_body(&mut app, BodyId(0));
+    app.update();
+    assert_eq!(common::trails(&mut app).len(), 1);
+}
+
+#[test]
+fn a_fading_trail_lasts_its_duration() {
+    // Half a second is 32 updates of one fixed tick each.
+    let mut app = running(OrphanedTrails::FadeOut(0.5));
+    despawn_body(&mut app, BodyId(0));
+    for _ in 0..28 {
+        app.update();
+    }
+    assert_eq!(common::trails(&mut app).len(), 2);
+    for _ in 0..8 {
+        app.update();
+    }
+    assert_eq!(common::trails(&mut app).len(), 1);
+}
+
+#[test]
+fn a_zero_fade_despawns_at_once() {
+    for duration in [0., -1., f32::NAN] {
+        let mut app = running(OrphanedTrails::FadeOut(duration));
+        despawn_body(&mut app, BodyId(0));
+        app.update();
+        assert_eq!(common::trails(&mut app).len(), 1, "FadeOut({duration})");
+    }
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use three_body::simulation::{step, Config};
+
+/// Largest distance, in scenario units, the two ways of summing may end
+/// up apart. They only round differently.
+const TOLERANCE: f64 = 1e-9;
+
+fn positions(parallel_gravity: bool) -> Vec<bevy::math::DVec3> {
+    let mut world = common::world(Config { parallel_gravity, ..common::three_bodies() });
+    step(&mut world, 200);
+    common::bodies(&mut world).into_iter().map(|(_, position, _)| position).collect()
+}
+
+#[test]
+fn parallel_matches_serial() {
+    let (serial, parallel) = (positions(false), positions(true));
+    for (serial, parallel) in serial.iter().zip(&parallel) {
+        assert!(serial.distance(*parallel) < TOLERANCE, "{serial} vs {parallel}");
+    }
+}
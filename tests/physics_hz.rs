@@ -0,0 +1,25 @@
+mod common;
+
+use std::time::Duration;
+use three_body::simulation::{Config, GravityPlugin, SimClock};
+
+#[test]
+fn physics_hz_sets_the_ticks_per_real_second() {
+    for physics_hz in [32., 64., 200.] {
+        let config = Config { physics_hz, timestep: 5., ..common::three_bodies() };
+        let mut app = common::app_with(GravityPlugin::new(config), Duration::from_millis(10));
+        // Applies the running state; the first frame has no elapsed time.
+        app.update();
+        let start = *app.world().resource::<SimClock>();
+        // One real second.
+        for _ in 0..100 {
+            app.update();
+        }
+        let end = *app.world().resource::<SimClock>();
+        let ticks = (end.steps - start.steps) as f64;
+        assert!((ticks - physics_hz).abs() <= 1., "{ticks} ticks at {physics_hz} Hz");
+        // `timestep` simulated seconds pass per real second, whatever the rate.
+        let simulated = end.elapsed - start.elapsed;
+        assert!((simulated - 5.).abs() <= 5. / physics_hz, "{simulated} s simulated at {physics_hz} Hz");
+    }
+}
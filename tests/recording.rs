@@ -0,0 +1,25 @@
+use bevy::color::LinearRgba;
+use bevy::math::DVec3;
+use three_body::recording::{RecordedBody, Recording};
+use three_body::simulation::BodyId;
+
+#[test]
+fn save_and_load_round_trip_exactly() {
+    let recording = Recording {
+        bodies: vec![
+            RecordedBody { id: BodyId(0), mass: 1.5, radius: 0.25, color: LinearRgba::new(1., 0.5, 0., 1.) },
+            RecordedBody { id: BodyId(7), mass: -2e-3, radius: 1e-9, color: LinearRgba::new(0.1, 0.2, 0.3, 0.4) },
+        ],
+        frames: (0..50)
+            .map(|tick| {
+                let t = f64::from(tick) / 7.;
+                vec![DVec3::new(t.sin(), t.cos(), -t), DVec3::new(1e300 * t, -1e-300, t.sqrt())]
+            })
+            .collect(),
+    };
+    let path = std::env::temp_dir().join(format!("three_body_round_trip_{}.3br", std::process::id()));
+    recording.save(&path).expect("recording should save");
+    let loaded = Recording::load(&path);
+    std::fs::remove_file(&path).ok();
+    assert_eq!(loaded.expect("recording should load"), recording);
+}
@@ -0,0 +1,119 @@
+//! Runs `data/figure_eight.ron` headless and compares the body positions
+//! against `data/figure_eight.trajectory`, recorded from a known-good
+//! build, to catch unintended changes to the physics.
+//!
+//! The reference holds one `step body x y z` row per body every
+//! `SAMPLE_INTERVAL` ticks, bodies numbered in spawn order. After an
+//! intentional change to the integrator or force law, check the new
+//! behavior is right by other means, then rewrite the file with
+//!
+//! ```sh
+//! UPDATE_REFERENCE=1 cargo test --test reference_trajectory
+//! ```
+//!
+//! and commit it along with the change.
+
+use std::fs;
+use std::path::PathBuf;
+use bevy::ecs::world::Command;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::simulation::{step, BodyId, Config, Position, ResetSimulation};
+
+/// Ticks between recorded samples.
+const SAMPLE_INTERVAL: usize = 100;
+/// Samples taken, so the run covers `SAMPLE_INTERVAL * SAMPLES` ticks:
+/// about two and a half turns of the figure-eight.
+const SAMPLES: usize = 10;
+/// Largest distance, in scenario units, a body may end up from its
+/// reference position. A step is plain IEEE arithmetic in a fixed order,
+/// so an unchanged build reproduces the file to the last bit; this only
+/// leaves room for a platform rounding differently. Any real change to
+/// the physics moves the bodies by far more.
+const TOLERANCE: f64 = 1e-9;
+
+fn data_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data").join(name)
+}
+
+/// Positions of every body, in spawn order.
+fn positions(world: &mut World) -> Vec<DVec3> {
+    let mut bodies: Vec<(BodyId, DVec3)> = world
+        .query::<(&BodyId, &Position)>()
+        .iter(world)
+        .map(|(id, position)| (*id, position.0))
+        .collect();
+    bodies.sort_by_key(|(id, _)| *id);
+    bodies.into_iter().map(|(_, position)| position).collect()
+}
+
+/// `(step, positions)` for every sample of a fresh run of the scenario.
+fn run() -> Vec<(usize, Vec<DVec3>)> {
+    let config = Config::load(data_path("figure_eight.ron")).expect("scenario should load");
+    let mut world = World::new();
+    ResetSimulation(config).apply(&mut world);
+    (1..=SAMPLES)
+        .map(|sample| {
+            step(&mut world, SAMPLE_INTERVAL);
+            (sample * SAMPLE_INTERVAL, positions(&mut world))
+        })
+        .collect()
+}
+
+fn write_reference(samples: &[(usize, Vec<DVec3>)]) {
+    let mut rows = String::from("# step body x y z\n");
+    for (step, positions) in samples {
+        for (body, position) in positions.iter().enumerate() {
+            rows += &format!("{step} {body} {:?} {:?} {:?}\n", position.x, position.y, position.z);
+        }
+    }
+    fs::write(data_path("figure_eight.trajectory"), rows).expect("reference should be writable");
+}
+
+/// `(step, body, position)` rows of the reference file.
+fn read_reference() -> Vec<(usize, usize, DVec3)> {
+    let text = fs::read_to_string(data_path("figure_eight.trajectory")).expect("reference should exist");
+    text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [step, body, x, y, z] = fields[..] else {
+                panic!("malformed reference row {line:?}");
+            };
+            let number = |field: &str| field.parse::<f64>().unwrap_or_else(|err| panic!("{field:?}: {err}"));
+            (
+                step.parse().expect("step should be an integer"),
+                body.parse().expect("body should be an integer"),
+                DVec3::new(number(x), number(y), number(z)),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn figure_eight_matches_reference() {
+    let samples = run();
+    if std::env::var_os("UPDATE_REFERENCE").is_some() {
+        write_reference(&samples);
+        return;
+    }
+
+    let reference = read_reference();
+    assert_eq!(reference.len(), SAMPLES * 3, "reference should have a row per body and sample");
+    for (step, body, expected) in reference {
+        let (_, positions) = samples.iter()
+            .find(|(sampled, _)| *sampled == step)
+            .unwrap_or_else(|| panic!("step {step} is not sampled"));
+        let actual = positions[body];
+        let error = actual.distance(expected);
+        assert!(
+            error <= TOLERANCE,
+            "body {body} at step {step} is at {actual}, {error:e} from the reference {expected}",
+        );
+    }
+}
+
+#[test]
+fn runs_are_deterministic() {
+    assert_eq!(run(), run());
+}
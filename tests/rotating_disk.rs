@@ -0,0 +1,30 @@
+mod common;
+
+use three_body::simulation::{step, Config};
+
+#[test]
+fn disk_stays_disk_shaped() {
+    let radius = 1e11;
+    let n = 200;
+    let config = Config::rotating_disk(n, radius, 2e30, 7);
+    let mut world = common::world(config);
+    // Half a turn of the rim, five turns of the inner edge.
+    for _ in 0..8 {
+        step(&mut world, 160);
+        let states = common::states(&mut world);
+        let center = states[0].1;
+        let mut spread = 0;
+        for (_, position, _) in &states[1..] {
+            let offset = *position - center;
+            assert!(offset.z.abs() < 1e-3 * radius, "particle left the plane: {offset}");
+            let r = offset.length() / radius;
+            assert!(r < 3., "particle flung out to {r} disk radii");
+            if !(0.1..1.5).contains(&r) {
+                spread += 1;
+            }
+        }
+        // Close passes heat the disk a little, but it must not collapse or
+        // fly apart.
+        assert!(spread * 20 < n, "{spread} of {n} particles left the disk");
+    }
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::diagnostics::total_energy;
+use three_body::simulation::{run_steps, BodyConfig, BodyId, Config};
+
+/// Equal up to rounding, as sums over the bodies in another order are.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-12 * a.abs().max(b.abs())
+}
+
+#[test]
+fn energy_drift_matches_total_energy() {
+    let config = common::three_bodies();
+    let g = config.gravitational_constant();
+    let mut app = common::app(config);
+    app.update();
+    let initial = total_energy(&common::states(app.world_mut()), g);
+
+    let summary = run_steps(&mut app, 500);
+    let final_energy = total_energy(&common::states(app.world_mut()), g);
+    assert_eq!(summary.steps, 500);
+    assert!(close(summary.initial_energy, initial), "{} != {initial}", summary.initial_energy);
+    assert!(close(summary.final_energy, final_energy), "{} != {final_energy}", summary.final_energy);
+    let drift = (final_energy - initial) / initial.abs() * 100.;
+    assert_ne!(drift, 0., "the run should drift at least a little");
+    assert!((summary.energy_drift_percent() - drift).abs() <= 1e-6 * drift.abs());
+}
+
+#[test]
+fn merges_are_counted_but_despawns_are_not() {
+    // Touching and at rest, so the inspiral merges them on the first tick.
+    let body = |x: f64| BodyConfig { radius: 0.5, ..common::body(1., DVec3::X * x, DVec3::ZERO) };
+    let config = Config {
+        gw_inspiral: true,
+        ..common::natural(vec![body(-0.4), body(0.4), body(50.)])
+    };
+    let mut app = common::app(config);
+    app.update();
+    let summary = run_steps(&mut app, 5);
+    assert_eq!((summary.bodies, summary.merged), (2, 1));
+
+    let mut app = common::app(common::three_bodies());
+    app.add_systems(FixedUpdate, |mut commands: Commands, bodies: Query<(Entity, &BodyId)>| {
+        for (entity, _) in bodies.iter().filter(|(_, id)| id.0 == 2) {
+            commands.entity(entity).despawn();
+        }
+    });
+    app.update();
+    let summary = run_steps(&mut app, 5);
+    assert_eq!((summary.bodies, summary.merged), (2, 0));
+}
+
+#[test]
+fn a_flung_body_counts_as_escaping() {
+    let speed = 0.5_f64.sqrt();
+    let config = common::natural(vec![
+        // A circular binary, bound to each other.
+        common::body(1., DVec3::new(-0.5, 0., 0.), DVec3::new(0., -speed, 0.)),
+        common::body(1., DVec3::new(0.5, 0., 0.), DVec3::new(0., speed, 0.)),
+        // Well past escape speed for the pair, heading out.
+        common::body(0.01, DVec3::new(20., 0., 0.), DVec3::new(3., 0., 0.)),
+        // A test particle on the same path escapes too.
+        BodyConfig { test_particle: true, ..common::body(0., DVec3::new(0., 20., 0.), DVec3::new(0., 3., 0.)) },
+    ]);
+    let mut app = common::app(config);
+    app.update();
+    let summary = run_steps(&mut app, 10);
+    assert_eq!(summary.escaping, 2);
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use bevy::ecs::world::Command;
+use three_body::simulation::{step, BodyId, BodyIndex, Config, SimClock};
+use three_body::snapshot::{snapshot, RestoreSnapshot};
+
+#[test]
+fn restore_brings_back_the_snapshot() {
+    let mut world = common::world(common::three_bodies());
+    step(&mut world, 50);
+    // A gap in the ids, so restoring has to put each body back under its own.
+    let middle = world.resource::<BodyIndex>().get(BodyId(1)).unwrap();
+    world.despawn(middle);
+    let taken = snapshot(&mut world);
+    let before = common::bodies(&mut world);
+    let clock = *world.resource::<SimClock>();
+
+    step(&mut world, 100);
+    assert_ne!(common::bodies(&mut world), before);
+    RestoreSnapshot(taken.clone()).apply(&mut world);
+
+    assert_eq!(common::bodies(&mut world), before);
+    assert_eq!(*world.resource::<SimClock>(), clock);
+    assert_eq!(snapshot(&mut world).next_id, taken.next_id);
+
+    // New bodies continue after the old ids rather than reusing the gap.
+    let template = world.resource::<Config>().initial_bodies[0].clone();
+    common::spawn(&mut world, &template);
+    let ids: Vec<BodyId> = common::bodies(&mut world).into_iter().map(|(id, ..)| id).collect();
+    assert_eq!(ids, [BodyId(0), BodyId(2), BodyId(3)]);
+}
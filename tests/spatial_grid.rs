@@ -0,0 +1,32 @@
+use bevy::math::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use three_body::spatial::SpatialGrid;
+
+#[test]
+fn grid_finds_the_same_pairs_as_brute_force() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let points: Vec<DVec3> = (0..300)
+        .map(|_| DVec3::new(rng.gen_range(-10.0..10.), rng.gen_range(-10.0..10.), rng.gen_range(-10.0..10.)))
+        .collect();
+    let reach = 1.5;
+    let mut expected = Vec::new();
+    for i in 0..points.len() {
+        for j in i + 1..points.len() {
+            if points[i].distance(points[j]) <= reach {
+                expected.push((i, j));
+            }
+        }
+    }
+    assert!(!expected.is_empty());
+
+    for cell_size in [reach / 8., reach / 3., reach, 4. * reach] {
+        let mut found: Vec<(usize, usize)> = SpatialGrid::from_points(cell_size, &points)
+            .candidate_pairs(reach)
+            .into_iter()
+            .filter(|&(i, j)| points[i].distance(points[j]) <= reach)
+            .collect();
+        found.sort_unstable();
+        assert_eq!(found, expected, "cell size {cell_size}");
+    }
+}
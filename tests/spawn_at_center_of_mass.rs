@@ -0,0 +1,25 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::diagnostics::DiagnosticsPlugin;
+use three_body::simulation::{BodyId, SimulationState};
+
+#[test]
+fn the_spawn_lands_on_the_center_of_mass() {
+    let mut app = common::app(common::three_bodies());
+    app.add_plugins(DiagnosticsPlugin);
+    // Paused, so nothing moves between measuring and spawning.
+    app.world_mut().resource_mut::<NextState<SimulationState>>().set(SimulationState::Stopped);
+    app.update();
+    let states = common::states(app.world_mut());
+    let total: f64 = states.iter().map(|(mass, ..)| mass).sum();
+    let center = states.iter().map(|(mass, position, _)| *mass * *position).sum::<DVec3>() / total;
+
+    common::tap(&mut app, KeyCode::KeyM);
+    let bodies = common::bodies(app.world_mut());
+    assert_eq!(bodies.len(), 4);
+    let (id, position, _) = bodies[3];
+    assert_eq!(id, BodyId(3));
+    assert!(position.distance(center) < 1e-12, "spawned at {position}, center of mass {center}");
+}
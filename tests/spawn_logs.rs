@@ -0,0 +1,58 @@
+mod common;
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use bevy::log::tracing_subscriber::layer::{Context, SubscriberExt};
+use bevy::log::tracing_subscriber::{Layer, Registry};
+use bevy::math::DVec3;
+use bevy::utils::tracing::field::{Field, Visit};
+use bevy::utils::tracing::{subscriber, Event, Level, Subscriber};
+
+/// Every field of a captured event, rendered with `Debug`.
+#[derive(Default, Debug)]
+struct Fields(Vec<(String, String)>);
+
+impl Fields {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(field, _)| field == name).map(|(_, value)| value.as_str())
+    }
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push((field.name().to_owned(), format!("{value:?}")));
+    }
+}
+
+/// Keeps the fields of every `debug` event.
+struct Capture(Arc<Mutex<Vec<Fields>>>);
+
+impl<S: Subscriber> Layer<S> for Capture {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        if *event.metadata().level() == Level::DEBUG {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields);
+        }
+    }
+}
+
+#[test]
+fn spawning_logs_the_body() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = Registry::default().with(Capture(events.clone()));
+    subscriber::with_default(subscriber, || {
+        common::world(common::natural(vec![
+            common::body(2.5, DVec3::new(1., 2., 3.), DVec3::new(0., 0.5, 0.)),
+        ]));
+    });
+
+    let events = events.lock().unwrap();
+    let spawned = events.iter()
+        .find(|fields| fields.get("message") == Some("body spawned"))
+        .unwrap_or_else(|| panic!("no spawn logged in {events:?}"));
+    assert_eq!(spawned.get("id"), Some("0"));
+    assert_eq!(spawned.get("mass"), Some("2.5"));
+    assert_eq!(spawned.get("position"), Some(format!("{:?}", DVec3::new(1., 2., 3.)).as_str()));
+    assert!(spawned.get("entity").is_some());
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use bevy::math::DVec3;
+use three_body::diagnostics::total_energy;
+use three_body::simulation::{step, Config};
+
+/// Largest relative energy error over ten periods of a tight, eccentric
+/// binary that goes round about twice a second, at `substeps` per tick.
+fn energy_drift(substeps: u32) -> f64 {
+    let config = Config {
+        substeps,
+        ..common::natural(vec![
+            common::body(20., DVec3::new(-0.5, 0., 0.), DVec3::new(0., -2., 0.)),
+            common::body(20., DVec3::new(0.5, 0., 0.), DVec3::new(0., 2., 0.)),
+        ])
+    };
+    let g = config.gravitational_constant();
+    let mut world = common::world(config);
+    let start = total_energy(&common::states(&mut world), g);
+    let mut drift: f64 = 0.;
+    for _ in 0..320 {
+        step(&mut world, 1);
+        let energy = total_energy(&common::states(&mut world), g);
+        drift = drift.max(((energy - start) / start).abs());
+    }
+    drift
+}
+
+#[test]
+fn more_substeps_drift_less() {
+    let drifts: Vec<f64> = [1, 4, 16].map(energy_drift).into();
+    for pair in drifts.windows(2) {
+        assert!(pair[1] < pair[0] / 2., "drifts {drifts:?} should fall with substeps");
+    }
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use bevy::math::DVec3;
+use three_body::simulation::{step, BodyConfig, Config};
+
+/// A planet around a star, plus `particle` if given.
+fn system(particle: Option<BodyConfig>) -> Config {
+    let mut bodies = vec![
+        common::body(1., DVec3::ZERO, DVec3::ZERO),
+        common::body(0.01, DVec3::new(1., 0., 0.), DVec3::new(0., 1., 0.)),
+    ];
+    bodies.extend(particle);
+    common::natural(bodies)
+}
+
+#[test]
+fn a_test_particle_pulls_on_nothing() {
+    // Massive and close, so it would show if it counted.
+    let particle = BodyConfig {
+        test_particle: true,
+        ..common::body(0.5, DVec3::new(0., 0.5, 0.), DVec3::new(-0.8, 0., 0.))
+    };
+    let mut with = common::world(system(Some(particle)));
+    let mut without = common::world(system(None));
+    for _ in 0..10 {
+        step(&mut with, 30);
+        step(&mut without, 30);
+        let (with, without) = (common::bodies(&mut with), common::bodies(&mut without));
+        assert_eq!(with[..2], without[..], "the particle changed the massive bodies' motion");
+        assert_ne!(with[2].1, DVec3::new(0., 0.5, 0.), "the particle should still move");
+    }
+}
@@ -0,0 +1,15 @@
+use three_body::simulation::Config;
+use three_body::units::UnitSystem;
+
+#[test]
+fn months_per_second_in_default_units() {
+    let config = Config::default().months_per_second(2.);
+    // 2 months of a 365-day year, in `Simulation` seconds.
+    assert!((config.timestep - 3.1536e7 / 12. * 2.).abs() < 1e-6);
+}
+
+#[test]
+fn days_per_second_follows_the_unit_system() {
+    let config = Config { units: UnitSystem::AstronomicalAuDay, ..Config::default() }.days_per_second(3.);
+    assert!((config.timestep - 3.).abs() < 1e-12);
+}
@@ -0,0 +1,58 @@
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::input::InputPlugin;
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use three_body::cursor::{CursorCoords, CursorPlugin, MainCamera};
+
+/// An app with a window and an orthographic main camera, zoomed and moved
+/// off the origin so the mapping isn't trivial.
+fn app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins((InputPlugin, CursorPlugin));
+    let window = app.world_mut().spawn((Window::default(), PrimaryWindow)).id();
+    app.world_mut().spawn((
+        GlobalTransform::from(Transform::from_xyz(5., -3., 10.)),
+        Projection::Orthographic(OrthographicProjection { scale: 0.08, ..default() }),
+        MainCamera,
+    ));
+    (app, window)
+}
+
+fn touch(app: &mut App, window: Entity, phase: TouchPhase, position: Vec2) {
+    app.world_mut().send_event(TouchInput { phase, position, window, force: None, id: 0 });
+}
+
+fn cursor(app: &App) -> DVec2 {
+    app.world().resource::<CursorCoords>().world()
+}
+
+#[test]
+fn touch_maps_like_the_mouse() {
+    let point = Vec2::new(200., 150.);
+
+    let (mut mouse_app, window) = app();
+    mouse_app.world_mut().get_mut::<Window>(window).unwrap().set_cursor_position(Some(point));
+    mouse_app.update();
+
+    let (mut touch_app, window) = app();
+    touch(&mut touch_app, window, TouchPhase::Started, point);
+    touch_app.update();
+
+    assert_ne!(cursor(&mouse_app), DVec2::ZERO);
+    assert_eq!(cursor(&touch_app), cursor(&mouse_app));
+}
+
+#[test]
+fn a_finger_holds_the_spawn_binding() {
+    let (mut app, window) = app();
+    touch(&mut app, window, TouchPhase::Started, Vec2::new(200., 150.));
+    app.update();
+    assert!(app.world().resource::<ButtonInput<MouseButton>>().just_pressed(MouseButton::Left));
+
+    touch(&mut app, window, TouchPhase::Ended, Vec2::new(220., 150.));
+    app.update();
+    let mouse = app.world().resource::<ButtonInput<MouseButton>>();
+    assert!(mouse.just_released(MouseButton::Left));
+    assert!(!mouse.pressed(MouseButton::Left));
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use bevy::prelude::*;
+use three_body::simulation::{BodyConfig, Config, TrailBlend};
+
+#[test]
+fn trail_blend_sets_the_trail_material() {
+    for (trail_blend, alpha_mode) in [
+        (TrailBlend::Opaque, AlphaMode::Opaque),
+        (TrailBlend::Alpha, AlphaMode::Blend),
+        (TrailBlend::Additive, AlphaMode::Add),
+    ] {
+        let mut config = Config { trail_blend, ..common::three_bodies() };
+        for body in &mut config.initial_bodies {
+            *body = BodyConfig { trail_color: Some(LinearRgba::new(4., 2., 1., 0.4)), ..body.clone() };
+        }
+        let mut app = common::app(config);
+        for _ in 0..3 {
+            app.update();
+        }
+        let trails = common::trails(&mut app);
+        assert_eq!(trails.len(), 3);
+        for trail in trails {
+            let handle = app.world().get::<Handle<StandardMaterial>>(trail).unwrap();
+            let material = app.world().resource::<Assets<StandardMaterial>>().get(handle).unwrap();
+            assert_eq!(material.alpha_mode, alpha_mode, "under {trail_blend:?}");
+            assert_eq!(material.base_color.alpha(), 0.4, "under {trail_blend:?}");
+        }
+    }
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use std::time::Duration;
+use bevy::prelude::*;
+use three_body::simulation::{GravityPlugin, SimClock};
+
+/// Physics ticks run and vertices in each trail's mesh after `seconds` of
+/// real time at `fps` frames per second.
+fn run(fps: u32, seconds: u32) -> (u64, Vec<usize>) {
+    let plugin = GravityPlugin::new(common::three_bodies());
+    let mut app = common::app_with(plugin, Duration::from_secs(1) / fps);
+    // Applies the running state; the first frame has no elapsed time.
+    app.update();
+    for _ in 0..fps * seconds {
+        app.update();
+    }
+    let mut lengths: Vec<usize> = common::trails(&mut app)
+        .into_iter()
+        .map(|trail| {
+            let handle = app.world().get::<Handle<Mesh>>(trail).unwrap();
+            app.world().resource::<Assets<Mesh>>().get(handle).unwrap().count_vertices()
+        })
+        .collect();
+    lengths.sort();
+    (app.world().resource::<SimClock>().steps, lengths)
+}
+
+#[test]
+fn trail_points_follow_ticks_not_frames() {
+    // Both frame lengths are whole nanoseconds, so both runs cover exactly
+    // the same time and tick count.
+    let (slow_ticks, slow) = run(32, 2);
+    let (fast_ticks, fast) = run(128, 2);
+    assert_eq!(slow_ticks, fast_ticks);
+    assert_eq!(slow.len(), 3);
+    assert!(slow.iter().all(|&points| points > 10), "trails barely grew: {slow:?}");
+    assert_eq!(slow, fast);
+}
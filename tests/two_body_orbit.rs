@@ -0,0 +1,70 @@
+mod common;
+
+use bevy::math::DVec3;
+use three_body::orbit::OrbitalElements;
+use three_body::simulation::{step, BodyConfig, Config};
+
+/// Elements of `bodies[1]` about `bodies[0]`, each given as `(mass,
+/// position, velocity)`.
+fn relative_orbit(config: &Config, bodies: &[(f64, DVec3, DVec3)]) -> OrbitalElements {
+    let [(m1, r1, v1), (m2, r2, v2)] = bodies[..] else {
+        panic!("expected two bodies, got {}", bodies.len());
+    };
+    let mu = config.gravitational_constant() * (m1 + m2);
+    OrbitalElements::from_state(r2 - r1, v2 - v1, mu).expect("orbit should not be degenerate")
+}
+
+fn configured(config: &Config) -> Vec<(f64, DVec3, DVec3)> {
+    config.initial_bodies.iter()
+        .map(|BodyConfig { mass, position, velocity, .. }| (*mass, *position, *velocity))
+        .collect()
+}
+
+#[test]
+fn eccentricity_matches_the_request() {
+    for e in [0., 0.3, 0.9, 1.5] {
+        let a = if e < 1. { 2e11 } else { -2e11 };
+        let config = Config::two_body_orbit(3e30, 1e30, a, e);
+        let orbit = relative_orbit(&config, &configured(&config));
+        assert!((orbit.eccentricity - e).abs() < 1e-9, "e = {e} gave {}", orbit.eccentricity);
+        assert!(orbit.true_anomaly.min(std::f64::consts::TAU - orbit.true_anomaly) < 1e-6);
+    }
+}
+
+#[test]
+fn eccentricity_holds_over_a_run() {
+    let config = Config { substeps: 8, ..Config::two_body_orbit(3e30, 1e30, 2e11, 0.5) };
+    let mut world = common::world(config.clone());
+    // A quarter of a period, at ten seconds per period.
+    step(&mut world, 160);
+    let orbit = relative_orbit(&config, &common::states(&mut world));
+    assert!((orbit.eccentricity - 0.5).abs() < 1e-3, "drifted to {}", orbit.eccentricity);
+}
+
+#[test]
+fn near_parabolic_orbits_are_built() {
+    for e in [1. - 1e-6, 1. + 1e-6] {
+        let a = 2e11 / (1. - e);
+        let config = Config::two_body_orbit(3e30, 1e30, a, e);
+        let orbit = relative_orbit(&config, &configured(&config));
+        assert!((orbit.eccentricity - e).abs() < 1e-6, "e = {e} gave {}", orbit.eccentricity);
+    }
+}
+
+#[test]
+#[should_panic(expected = "no Kepler orbit")]
+fn parabolic_orbit_is_rejected() {
+    Config::two_body_orbit(3e30, 1e30, 2e11, 1.);
+}
+
+#[test]
+#[should_panic(expected = "no Kepler orbit")]
+fn negative_eccentricity_is_rejected() {
+    Config::two_body_orbit(3e30, 1e30, 2e11, -0.5);
+}
+
+#[test]
+#[should_panic(expected = "no Kepler orbit")]
+fn ellipse_with_negative_axis_is_rejected() {
+    Config::two_body_orbit(3e30, 1e30, -2e11, 0.5);
+}
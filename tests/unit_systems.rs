@@ -0,0 +1,39 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::World;
+use three_body::simulation::{step, BodyConfig, Config};
+use three_body::units::UnitSystem;
+
+#[test]
+fn earth_takes_a_year_of_one_day_steps() {
+    let units = UnitSystem::AstronomicalAuDay;
+    let config = Config {
+        units,
+        initial_bodies: vec![
+            BodyConfig::astronomical(units, 1., DVec3::ZERO, DVec3::ZERO),
+            BodyConfig::astronomical(units, 3.003e-6, DVec3::X, DVec3::new(0., 29.78, 0.)),
+        ],
+        ..Config::default()
+    };
+    // One simulated day per physics step.
+    let config = config.clone().days_per_second(config.physics_hz);
+    let mut world = common::world(config);
+
+    let mut angle = 0.;
+    let mut steps = 0;
+    while angle < std::f64::consts::TAU {
+        let before = earth_offset(&mut world);
+        step(&mut world, 1);
+        let after = earth_offset(&mut world);
+        angle += before.cross(after).z.atan2(before.dot(after));
+        steps += 1;
+        assert!(steps < 400, "no full orbit after {steps} days");
+    }
+    assert!((364..=367).contains(&steps), "a year took {steps} steps");
+}
+
+fn earth_offset(world: &mut World) -> DVec3 {
+    let bodies = common::bodies(world);
+    bodies[1].1 - bodies[0].1
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use three_body::cursor::CursorCoords;
+use three_body::selection::{Selected, SelectionPlugin};
+use three_body::simulation::{BodyId, SimulationState};
+
+#[test]
+fn nudge_changes_only_the_selected_body() {
+    let config = common::three_bodies();
+    let nudge = config.velocity_nudge;
+    let mut app = common::app(config);
+    app.init_resource::<CursorCoords>().add_plugins(SelectionPlugin);
+    app.update();
+    app.world_mut().resource_mut::<NextState<SimulationState>>().set(SimulationState::Stopped);
+    app.update();
+    let selected = app.world_mut()
+        .query::<(Entity, &BodyId)>()
+        .iter(app.world())
+        .find(|(_, id)| id.0 == 1)
+        .unwrap()
+        .0;
+    app.world_mut().entity_mut(selected).insert(Selected);
+
+    let before = common::bodies(app.world_mut());
+    common::tap(&mut app, KeyCode::ArrowRight);
+    common::tap(&mut app, KeyCode::ArrowUp);
+    let after = common::bodies(app.world_mut());
+    assert_eq!(after[0], before[0]);
+    assert_eq!(after[2], before[2]);
+    assert_eq!(after[1].1, before[1].1, "a paused body shouldn't move");
+    let change = after[1].2 - before[1].2;
+    assert!((change - DVec3::new(nudge, nudge, 0.)).length() < 1e-12, "velocity changed by {change}");
+}
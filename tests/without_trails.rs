@@ -0,0 +1,19 @@
+mod common;
+
+use std::time::Duration;
+use three_body::simulation::{GravityPlugin, SimClock};
+
+#[test]
+fn gravity_runs_without_the_trail_plugin() {
+    let config = common::three_bodies();
+    let frame = Duration::from_secs_f64(1. / config.physics_hz);
+    let mut app = common::app_with(GravityPlugin::new(config).trails(false), frame);
+    app.update();
+    let start = common::bodies(app.world_mut());
+    for _ in 0..20 {
+        app.update();
+    }
+    assert!(app.world().resource::<SimClock>().steps >= 20);
+    assert_ne!(common::bodies(app.world_mut()), start, "the bodies should move");
+    assert!(common::trails(&mut app).is_empty());
+}